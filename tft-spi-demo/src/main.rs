@@ -1,61 +1,328 @@
 pub mod tft_display;
 pub mod tft_spi;
 pub mod tft_touch;
+pub mod tft_ui;
 mod util;
 
-use std::{error::Error, result, thread};
-use std::time::Duration;
+use std::{env, error::Error, result};
+use std::time::{Duration, Instant};
 
-use tft_display::{color::Color, enums::Rotate, tft_display::TftDisplay};
+use tft_display::{
+    color::Color, enums::Align, enums::Rotate, geometry::Rect, tft_display::TftDisplay,
+};
 use tft_spi::TftSpiImpl;
 // use tft_spi::TftSpi;
-//use tft_touch::tft_touch::TftTouch;
+use tft_touch::tft_touch::TftTouch;
+use tft_ui::{
+    animation::{Easing, FrameClock, Tween},
+    button::Button,
+    chart::{BarChart, LineChart, Sparkline},
+    dialog::Dialog,
+    gauge::{Dial, Gauge, Meter, Zone},
+    keyboard::{KeyInput, Keyboard},
+    label::Label,
+    layout::Layout,
+    list_view::{ListItem, ListView},
+    screen::{ScreenManager, Transition},
+    style::Style,
+    tab_bar::Tab,
+    text_field::TextField,
+    theme::Theme,
+    toast::Toast,
+    ui::Ui,
+    widget::WidgetEvent,
+};
+
+/// Frames per second sent to the display
+const FPS: u32 = 30;
+/// Longest wait between touch polls
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// How long the Executed title glows after running an action
+const FADE_TIME: Duration = Duration::from_secs(1);
+/// Actions on the Executed page, run again from their buttons
+const ACTIONS: [&str; 3] = ["Lights off", "Heat on", "Lock doors"];
 
 fn main() -> result::Result<(), Box<dyn Error>> {
     // let tft_spi: Box<dyn TftSpi> = Box::new(TftSpiImpl::new());
     let tft_spi = TftSpiImpl::new();
 
-    // let _touch = TftTouch::new(tft_spi.clone());
+    let mut touch = TftTouch::new(tft_spi.clone());
     let mut display = TftDisplay::new(tft_spi);
     display.initialize()?;
 
     display.init_screen_size(0, 0, 320, 480);
 
-    display.set_rotation(Rotate::Degrees0);
-    display.fill_screen(Color::BLUE)?;
-    display.fill_rectangle(0, 480-40, 120, 40, Color::GREEN)?;
-    thread::sleep(Duration::from_secs(1));
+    show_rotation(&mut display, Rotate::Degrees0, Color::BLUE)?;
+    show_rotation(&mut display, Rotate::Degrees90, Color::RED)?;
+    show_rotation(&mut display, Rotate::Degrees180, Color::YELLOW)?;
+    show_rotation(&mut display, Rotate::Degrees270, Color::MAGENTA)?;
+
+    // Branding comes from an optional theme file given on the command line
+    let theme = match env::args().nth(1) {
+        Some(path) => Theme::open(path)?,
+        None => Theme::dark(),
+    };
+    let style = theme.style()?;
+    show_scrolling(&mut display, &style)?;
+
+    // Lists are redrawn when they scroll in landscape, the display can't scroll them
+    display.set_rotation(Rotate::Degrees90)?;
+    touch.sync(&display);
+    let mut screens = ScreenManager::for_display(&display, style.clone());
+    let tabs = [
+        ("Executed", Color::RED, Color::WHITE),
+        ("Scheduled", Color::YELLOW, Color::BLACK),
+        ("Routines", Color::GREY, Color::WHITE),
+        ("Scenes", Color::MAGENTA, Color::WHITE),
+    ];
+    let mut rename = None;
+    let mut trends = None;
+    let mut hvac = None;
+    let mut actions = Vec::new();
+    let mut executed_title = None;
+    let mut confirm = None;
+    let mut started = None;
+    for (name, _, _) in tabs {
+        let mut ui = Ui::for_display(&display, style.clone());
+        let title = ui.add(Label::new(Rect::default(), name).with_align(Align::Center));
+        let mut rows = vec![Layout::widget(title).fixed(40)];
+        if name == "Executed" {
+            // Temperature and energy usage trends, fed with a sample every second
+            let temperature = ui.add(LineChart::new(Rect::default()).with_unit("C"));
+            let energy = ui.add(BarChart::new(Rect::default()).with_unit("kW"));
+            let sparkline = ui.add(Sparkline::new(Rect::default()));
+            rows[0] = Layout::row([Layout::widget(title), Layout::widget(sparkline).fixed(120)])
+                .spacing(8)
+                .fixed(40);
+            rows.extend([Layout::widget(temperature), Layout::widget(energy)]);
+            trends = Some((temperature, energy, sparkline));
+
+            // Actions act on real devices, so they ask first
+            actions = ACTIONS
+                .map(|action| ui.add(Button::new(Rect::default(), action)))
+                .into();
+            let buttons = actions.iter().map(|&button| Layout::widget(button));
+            rows.push(Layout::row(buttons).spacing(8).fixed(40));
+            let dialog = Dialog::new(Rect::from_size(280, 180), "Are you sure?", "")
+                .with_buttons(["Cancel", "Run"]);
+            executed_title = Some(title);
+            confirm = Some(ui.add(dialog));
+            started = Some(ui.add(Toast::new(Rect::from_size(240, 40), "")));
+        }
+        if name == "Scheduled" {
+            // HVAC load, supply temperature and power draw
+            let load = Gauge::new(Rect::default(), 0.0, 100.0, 0.0)
+                .with_unit("%")
+                .with_label("Load")
+                .with_threshold(80.0, Color::RED);
+            let supply = Dial::new(Rect::default(), 10.0, 30.0, 20.0)
+                .with_unit("C")
+                .with_decimals(1)
+                .with_zone(Zone::new(18.0, 24.0, Color::GREEN))
+                .with_zone(Zone::new(26.0, 30.0, Color::RED))
+                .with_easing(Easing::Spring);
+            let power = Meter::new(Rect::default(), 0.0, 5.0, 0.0)
+                .with_segments(20, 2)
+                .with_zone(Zone::new(0.0, 3.0, Color::GREEN))
+                .with_threshold(4.0, Color::RED);
+            let (load, supply, power) = (ui.add(load), ui.add(supply), ui.add(power));
+            let row = Layout::row([Layout::widget(load), Layout::widget(supply)]).spacing(8);
+            rows.extend([row, Layout::widget(power).fixed(24)]);
+            hvac = Some((load, supply, power));
+        }
+        if name == "Routines" {
+            let button = ui.add(Button::new(Rect::default(), "Rename device"));
+            rename = Some(button);
+            let row = Layout::row([Layout::widget(button).fixed(200)]).align(Align::Center);
+            rows.push(row.fixed(48));
+        }
+        if name == "Routines" || name == "Scenes" {
+            let items = (1..=30)
+                .map(|n| ListItem::new(format!("{name} {n}")).with_detail(format!("{}:00", n % 24)))
+                .collect();
+            let list = ui.add(ListView::new(Rect::default(), items));
+            rows.push(Layout::widget(list));
+        }
+        ui.set_layout(Layout::column(rows).padding(8).spacing(8));
+        screens.add_screen(name, ui);
+    }
 
-    display.set_rotation(Rotate::Degrees90);
-    display.fill_screen(Color::RED)?;
-    display.fill_rectangle(0, 320-40, 120, 40, Color::GREEN)?;
-    thread::sleep(Duration::from_secs(1));
+    // Device name entry, pushed over the Routines page
+    let mut ui = Ui::for_display(&display, style.clone());
+    let mut field = TextField::new(Rect::default(), "tft-panel").with_placeholder("Device name");
+    field.set_focused(true);
+    let field = ui.add(field);
+    let keyboard = ui.add(Keyboard::qwerty(Rect::default()));
+    let rows = [Layout::widget(field).fixed(40), Layout::widget(keyboard)];
+    ui.set_layout(Layout::column(rows).padding(4).spacing(4));
+    screens.add_screen("Rename", ui);
 
-    display.set_rotation(Rotate::Degrees180);
-    display.fill_screen(Color::YELLOW)?;
-    display.fill_rectangle(0, 480-40, 120, 40, Color::GREEN)?;
-    thread::sleep(Duration::from_secs(1));
+    let tabs = tabs.map(|(name, face, text)| {
+        (name.to_string(), Tab::new(name).with_colors(face, text))
+    });
+    screens.set_tab_bar(tabs.into(), 40);
 
-    display.set_rotation(Rotate::Degrees270);
-    display.fill_screen(Color::MAGENTA)?;
-    display.fill_rectangle(0, 320-40, 120, 40, Color::GREEN)?;
-    thread::sleep(Duration::from_secs(1));
+    let mut next_sample = Instant::now();
+    let mut samples = 0u32;
+    let mut pending = None;
+    let mut frames = FrameClock::new(FPS);
+    loop {
+        if Instant::now() >= next_sample {
+            next_sample += Duration::from_secs(1);
+            samples += 1;
+            let t = samples as f32;
+            let ui = screens.screen_mut("Executed");
+            if let (Some((temperature, energy, sparkline)), Some(ui)) = (trends, ui) {
+                if let Some(chart) = ui.get_mut::<LineChart>(temperature) {
+                    chart.push(21.0 + 2.0 * (t / 60.0).sin());
+                }
+                if let Some(chart) = ui.get_mut::<BarChart>(energy) {
+                    chart.push(0.5 + 0.4 * (t / 17.0).sin().abs());
+                }
+                if let Some(chart) = ui.get_mut::<Sparkline>(sparkline) {
+                    chart.push((t / 5.0).sin() + (t / 2.0).cos() / 3.0);
+                }
+            }
+            let ui = screens.screen_mut("Scheduled");
+            if let (Some((load, supply, power)), Some(ui)) = (hvac, ui) {
+                if let Some(gauge) = ui.get_mut::<Gauge>(load) {
+                    gauge.set_value(50.0 + 45.0 * (t / 7.0).sin());
+                }
+                if let Some(dial) = ui.get_mut::<Dial>(supply) {
+                    dial.set_value(21.0 + 6.0 * (t / 11.0).sin());
+                }
+                if let Some(meter) = ui.get_mut::<Meter>(power) {
+                    meter.set_value(2.5 + 2.5 * (t / 3.0).sin());
+                }
+            }
+        }
 
-    display.set_rotation(Rotate::Degrees90);
-    display.fill_screen(Color::BLACK)?;
-    // display.draw_text(10, 10, "Hello, World!", Color::WHITE, Color::BLUE, 4);
+        if let Some(event) = touch.poll()? {
+            let on_executed = screens.current() == Some("Executed");
+            match screens.handle_touch(event) {
+                Some((id, WidgetEvent::Clicked)) if on_executed && actions.contains(&id) => {
+                    let index = actions.iter().position(|&action| action == id);
+                    let ui = screens.screen_mut("Executed");
+                    if let (Some(index), Some(confirm), Some(ui)) = (index, confirm, ui) {
+                        if let Some(dialog) = ui.get_mut::<Dialog>(confirm) {
+                            dialog.set_message(format!("Run \"{}\" now?", ACTIONS[index]));
+                        }
+                        ui.open_dialog(confirm);
+                        pending = Some(index);
+                    }
+                }
+                Some((id, WidgetEvent::Selected(button))) if on_executed && Some(id) == confirm => {
+                    let ui = screens.screen_mut("Executed");
+                    if let (1, Some(index), Some(started), Some(ui)) =
+                        (button, pending.take(), started, ui)
+                    {
+                        if let Some(toast) = ui.get_mut::<Toast>(started) {
+                            toast.set_text(format!("{} started", ACTIONS[index]));
+                        }
+                        ui.open_toast(started, Duration::from_secs(2));
+                        if let Some(title) = executed_title {
+                            // The title glows green and fades back
+                            let fade = Tween::new(Color::GREEN, style.foreground, FADE_TIME);
+                            ui.animate(title, fade, |label: &mut Label, color| {
+                                label.set_color(Some(color));
+                            });
+                        }
+                    }
+                }
+                Some((id, WidgetEvent::Clicked)) if Some(id) == rename => {
+                    screens.push("Rename", Transition::SlideUp);
+                }
+                Some((_, WidgetEvent::Key(KeyInput::Enter))) => {
+                    screens.pop(Transition::SlideDown);
+                }
+                Some((_, WidgetEvent::Key(input))) => {
+                    if let Some(ui) = screens.screen_mut("Rename") {
+                        if let Some(field) = ui.get_mut::<TextField>(field) {
+                            field.apply(input);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        // Everything changed since the last frame goes out together
+        if frames.tick() {
+            screens.flush(&mut display)?;
+        }
+        frames.wait(POLL_INTERVAL);
+    }
+}
+
+/// Glides a full-width list to its end in portrait, where the display scrolls it and
+/// only the rows moved in are sent
+fn show_scrolling(display: &mut TftDisplay, style: &Style) -> tft_display::tft_display::Result<()> {
+    display.set_rotation(Rotate::Degrees0)?;
 
-    display.fill_rectangle(0, 280, 120, 40, Color::RED)?;
-    // display.draw_text(12, 292, "Executed", Color::WHITE, Color::RED, 2);
+    let mut ui = Ui::for_display(display, style.clone());
+    let items = (1..=60)
+        .map(|n| ListItem::new(format!("Item {n}")))
+        .collect();
+    let list = ui.add(ListView::new(Rect::default(), items));
+    ui.set_layout(Layout::column([Layout::widget(list)]));
+    let end = ui.get::<ListView>(list).map_or(0.0, ListView::max_offset);
 
-    display.fill_rectangle(120, 280, 120, 40, Color::YELLOW)?;
-    // // display.draw_text(126, 292, "Scheduled", Color::BLACK, Color::YELLOW, 2);
+    let mut screens = ScreenManager::for_display(display, style.clone());
+    screens.add_screen("Scrolling", ui);
+    screens.set_scroll_widget("Scrolling", Some(list));
+
+    let glide = Tween::new(0.0, end, Duration::from_secs(3));
+    let mut frames = FrameClock::new(FPS);
+    loop {
+        frames.wait(frames.interval());
+        if !frames.tick() {
+            continue;
+        }
+        let now = Instant::now();
+        let ui = screens.screen_mut("Scrolling");
+        if let Some(list) = ui.and_then(|ui| ui.get_mut::<ListView>(list)) {
+            list.set_offset(glide.value_at(now));
+        }
+        screens.flush(display)?;
+        if glide.is_done(now) {
+            return Ok(());
+        }
+    }
+}
 
-    display.fill_rectangle(240, 280, 120, 40, Color::BLACK)?;
-    // // display.draw_text(252, 292, "Routines", Color::BLACK, Color::MAGENTA, 2);
+/// Fills the screen in `rotation` and bounces a bar along the bottom edge
+fn show_rotation(
+    display: &mut TftDisplay,
+    rotation: Rotate,
+    color: Color,
+) -> tft_display::tft_display::Result<()> {
+    display.set_rotation(rotation)?;
+    display.fill_screen(color)?;
 
-    display.fill_rectangle(360, 280, 120, 40, Color::MAGENTA)?;
-    // // display.draw_text(384, 292, "Scenes", Color::WHITE, Color::BLACK, 2);
+    let (width, height) = (display.width(), display.height());
+    let (bar_width, bar_height) = (120, 40);
+    let y = height - bar_height;
+    display.fill_rectangle(0, y, bar_width, bar_height, Color::GREEN)?;
 
+    // Only the columns the bar leaves and enters are filled each frame
+    let slide = Tween::new(0, (width - bar_width) as i32, Duration::from_secs(1))
+        .with_easing(Easing::Bounce);
+    let mut frames = FrameClock::new(FPS);
+    let mut x = 0;
+    while x != slide.to() {
+        frames.wait(frames.interval());
+        if !frames.tick() {
+            continue;
+        }
+        let next = slide.value_at(Instant::now());
+        let (from, to) = (x.min(next) as u16, x.max(next) as u16);
+        let (left, entered) = if next > x {
+            (from, from + bar_width)
+        } else {
+            (from + bar_width, from)
+        };
+        display.fill_rectangle(left, y, to - from, bar_height, color)?;
+        display.fill_rectangle(entered, y, to - from, bar_height, Color::GREEN)?;
+        x = next;
+    }
     Ok(())
 }
@@ -0,0 +1,253 @@
+use std::f32::consts::PI;
+
+use crate::tft_display::{geometry::Rect, tft_display::Result};
+
+/// Calls `plot` for every pixel of the line from (`x0`, `y0`) to (`x1`, `y1`) that can
+/// fall in `clip`
+///
+/// Each pixel is computed from its step along the longer axis, so a line reaching far
+/// outside `clip` only costs the steps inside it.
+pub fn bresenham(
+    x0: i32,
+    y0: i32,
+    x1: i32,
+    y1: i32,
+    clip: Rect,
+    mut plot: impl FnMut(i32, i32) -> Result<()>,
+) -> Result<()> {
+    let (dx, dy) = (x1 as i64 - x0 as i64, y1 as i64 - y0 as i64);
+    let steps = dx.abs().max(dy.abs());
+    if steps == 0 {
+        return plot(x0, y0);
+    }
+    let from = (x0 as f64, y0 as f64);
+    let Some((enter, leave)) = clip_segment(from, (x1 as f64, y1 as f64), clip, 1.0) else {
+        return Ok(());
+    };
+    let first = ((enter * steps as f64).floor() as i64).max(0);
+    let last = ((leave * steps as f64).ceil() as i64).min(steps);
+
+    // Offset along an axis at step `k`, rounded with halves away from the start like
+    // the error term does
+    let offset = |k: i64, delta: i64| {
+        let moved = (2 * k as i128 * delta.abs() as i128 + steps as i128) / (2 * steps as i128);
+        moved as i64 * delta.signum()
+    };
+    for k in first..=last {
+        plot((x0 as i64 + offset(k, dx)) as i32, (y0 as i64 + offset(k, dy)) as i32)?;
+    }
+    Ok(())
+}
+
+/// Range of `t`, 0 at `from` and 1 at `to`, where the segment is within `margin` of
+/// `clip`, `None` if it misses (Liang–Barsky)
+pub fn clip_segment(
+    from: (f64, f64),
+    to: (f64, f64),
+    clip: Rect,
+    margin: f64,
+) -> Option<(f64, f64)> {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let (left, top) = (clip.x as f64 - margin, clip.y as f64 - margin);
+    let (right, bottom) = (clip.right() as f64 + margin, clip.bottom() as f64 + margin);
+
+    let (mut enter, mut leave) = (0.0f64, 1.0f64);
+    // Each edge as the direction towards its outside and the distance inside it
+    for (outward, inside) in [
+        (-dx, from.0 - left),
+        (dx, right - from.0),
+        (-dy, from.1 - top),
+        (dy, bottom - from.1),
+    ] {
+        if outward == 0.0 {
+            if inside < 0.0 {
+                return None;
+            }
+        } else if outward < 0.0 {
+            enter = enter.max(inside / outward);
+        } else {
+            leave = leave.min(inside / outward);
+        }
+    }
+    (enter <= leave).then_some((enter, leave))
+}
+
+/// Xiaolin Wu's line, calls `plot` with each pixel's coverage
+///
+/// Coordinates are pixel centers, so (0.0, 0.0) lies fully on the top left pixel.
+pub fn wu_line(
+    mut x0: f32,
+    mut y0: f32,
+    mut x1: f32,
+    mut y1: f32,
+    mut plot: impl FnMut(i32, i32, u8) -> Result<()>,
+) -> Result<()> {
+    let steep = (y1 - y0).abs() > (x1 - x0).abs();
+    if steep {
+        std::mem::swap(&mut x0, &mut y0);
+        std::mem::swap(&mut x1, &mut y1);
+    }
+    if x0 > x1 {
+        std::mem::swap(&mut x0, &mut x1);
+        std::mem::swap(&mut y0, &mut y1);
+    }
+
+    let gradient = if x1 == x0 { 1.0 } else { (y1 - y0) / (x1 - x0) };
+    let mut plot = |major: i32, minor: i32, intensity: f32| {
+        let alpha = (intensity.clamp(0.0, 1.0) * 255.0).round() as u8;
+        if steep {
+            plot(minor, major, alpha)
+        } else {
+            plot(major, minor, alpha)
+        }
+    };
+
+    // End points, weighted by how much of their pixel they cover
+    let mut endpoint = |x: f32, y: f32, gap: f32| -> Result<(i32, f32)> {
+        let x_end = x.round();
+        let y_end = y + gradient * (x_end - x);
+        let y_floor = y_end.floor();
+        let fraction = y_end - y_floor;
+        plot(x_end as i32, y_floor as i32, (1.0 - fraction) * gap)?;
+        plot(x_end as i32, y_floor as i32 + 1, fraction * gap)?;
+        Ok((x_end as i32, y_end))
+    };
+    let (start, start_y) = endpoint(x0, y0, 1.0 - (x0 + 0.5).fract())?;
+    let (end, _) = endpoint(x1, y1, (x1 + 0.5).fract())?;
+
+    let mut y = start_y + gradient;
+    for x in start + 1..end {
+        let y_floor = y.floor();
+        let fraction = y - y_floor;
+        plot(x, y_floor as i32, 1.0 - fraction)?;
+        plot(x, y_floor as i32 + 1, fraction)?;
+        y += gradient;
+    }
+    Ok(())
+}
+
+/// Coverage of a pixel whose center lies `distance` inside an edge (negative outside)
+pub fn coverage(distance: f32) -> u8 {
+    ((distance + 0.5).clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Pixel rectangle around a circle
+pub fn bounds(cx: f32, cy: f32, radius: f32) -> Rect {
+    let x = (cx - radius).floor() as i32;
+    let y = (cy - radius).floor() as i32;
+    let size = (radius * 2.0).ceil() as u16 + 1;
+    Rect::new(x, y, size, size)
+}
+
+/// Calls `draw` for every pixel of `bounds` inside `clip`, with its position and center
+pub fn for_each_pixel(
+    bounds: Rect,
+    clip: Rect,
+    mut draw: impl FnMut(i32, i32, f32, f32) -> Result<()>,
+) -> Result<()> {
+    let Some(area) = bounds.intersect(clip) else {
+        return Ok(());
+    };
+    for y in area.y..area.bottom() {
+        for x in area.x..area.right() {
+            draw(x, y, x as f32, y as f32)?;
+        }
+    }
+    Ok(())
+}
+
+/// Stroked arc with round caps, evaluated as a distance field
+pub struct Arc {
+    cx: f32,
+    cy: f32,
+    radius: f32,
+    half_thickness: f32,
+    /// Start angle in radians, normalized to 0..2π
+    start: f32,
+    /// Angular length in radians
+    sweep: f32,
+}
+
+impl Arc {
+    pub fn new(cx: f32, cy: f32, radius: f32, thickness: f32, start_angle: f32, end_angle: f32) -> Self {
+        let sweep = (end_angle - start_angle).clamp(-360.0, 360.0);
+        // Always sweep clockwise from the start
+        let (start, sweep) = if sweep < 0.0 {
+            (end_angle, -sweep)
+        } else {
+            (start_angle, sweep)
+        };
+
+        Self {
+            cx,
+            cy,
+            radius,
+            half_thickness: thickness / 2.0,
+            start: start.to_radians().rem_euclid(2.0 * PI),
+            sweep: sweep.to_radians(),
+        }
+    }
+
+    /// Coverage of the pixel centered at (`px`, `py`)
+    pub fn coverage(&self, px: f32, py: f32) -> u8 {
+        let (dx, dy) = (px - self.cx, py - self.cy);
+        let angle = (dy.atan2(dx) - self.start).rem_euclid(2.0 * PI);
+
+        let distance = if angle <= self.sweep || self.sweep >= 2.0 * PI {
+            ((dx * dx + dy * dy).sqrt() - self.radius).abs()
+        } else {
+            // Closest of the two round caps
+            let cap = |angle: f32| {
+                let (x, y) = (self.cx + self.radius * angle.cos(), self.cy + self.radius * angle.sin());
+                ((px - x).powi(2) + (py - y).powi(2)).sqrt()
+            };
+            cap(self.start).min(cap(self.start + self.sweep))
+        };
+        coverage(self.half_thickness - distance)
+    }
+}
+
+/// Stroked line segment with round caps, evaluated as a distance field
+pub struct Segment {
+    x0: f32,
+    y0: f32,
+    dx: f32,
+    dy: f32,
+    length_squared: f32,
+    half_thickness: f32,
+}
+
+impl Segment {
+    pub fn new(x0: f32, y0: f32, x1: f32, y1: f32, thickness: f32) -> Self {
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        Self {
+            x0,
+            y0,
+            dx,
+            dy,
+            length_squared: dx * dx + dy * dy,
+            half_thickness: thickness / 2.0,
+        }
+    }
+
+    /// Pixel rectangle around the stroke
+    pub fn bounds(&self) -> Rect {
+        let pad = self.half_thickness + 1.0;
+        let (x1, y1) = (self.x0 + self.dx, self.y0 + self.dy);
+        let (left, top) = ((self.x0.min(x1) - pad).floor(), (self.y0.min(y1) - pad).floor());
+        let (right, bottom) = ((self.x0.max(x1) + pad).ceil(), (self.y0.max(y1) + pad).ceil());
+        Rect::new(left as i32, top as i32, (right - left) as u16 + 1, (bottom - top) as u16 + 1)
+    }
+
+    /// Coverage of the pixel centered at (`px`, `py`)
+    pub fn coverage(&self, px: f32, py: f32) -> u8 {
+        let (vx, vy) = (px - self.x0, py - self.y0);
+        let t = if self.length_squared > 0.0 {
+            ((vx * self.dx + vy * self.dy) / self.length_squared).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let distance = ((vx - t * self.dx).powi(2) + (vy - t * self.dy).powi(2)).sqrt();
+        coverage(self.half_thickness - distance)
+    }
+}
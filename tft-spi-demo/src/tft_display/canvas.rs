@@ -0,0 +1,424 @@
+use crate::tft_display::{
+    antialias,
+    color::Color,
+    dither::{dither_rgb888, Dither, Ditherer},
+    enums::{Align, GradientDirection},
+    error::Error,
+    fill::{self, Pattern},
+    font::{text, Font},
+    geometry::Rect,
+    image::Image,
+    region::Region,
+    sprite::Sprite,
+    tft_display::Result,
+};
+
+/// Something pixels can be drawn on: the display itself, a `Framebuffer` or a `Region`
+/// of either
+///
+/// All coordinates are in the canvas' own space and everything is clipped to `clip()`.
+/// Implementations provide clipping, solid fills and row streaming, everything else is
+/// built on top of those.
+pub trait Canvas {
+    fn size(&self) -> (u16, u16);
+
+    /// Area drawing is currently restricted to
+    fn clip(&self) -> Rect;
+
+    /// Restricts drawing to `rect` within the current clip until the matching `pop_clip`
+    fn push_clip(&mut self, rect: Rect);
+
+    fn pop_clip(&mut self) -> Option<Rect>;
+
+    /// Dithering used for decoded images drawn without one
+    fn dither(&self) -> Dither;
+
+    fn fill_rect(&mut self, rect: Rect, color: Color) -> Result<()>;
+
+    /// Draws `rect` row by row, `row` appends all `rect.w` pixels of the given row
+    ///
+    /// Rows outside the clip may be skipped.
+    fn draw_rows(&mut self, rect: Rect, row: &mut dyn FnMut(u16, &mut Vec<Color>)) -> Result<()>;
+
+    /// Reads a pixel back, `None` outside the canvas or if it can't be read
+    fn pixel(&self, x: i32, y: i32) -> Option<Color>;
+
+    /// The whole canvas
+    fn bounds(&self) -> Rect {
+        let (w, h) = self.size();
+        Rect::from_size(w, h)
+    }
+
+    /// Drawing context for `rect`, with coordinates relative to its top left corner and
+    /// everything outside clipped
+    fn region(&mut self, rect: Rect) -> Region<'_, Self>
+    where
+        Self: Sized,
+    {
+        Region::new(self, rect)
+    }
+
+    fn fill(&mut self, color: Color) -> Result<()> {
+        self.fill_rect(self.bounds(), color)
+    }
+
+    /// Draws a block of `rect.w` x `rect.h` pixels
+    fn draw_image_rect(&mut self, rect: Rect, pixels: &[Color]) -> Result<()> {
+        check_len(pixels.len(), rect)?;
+        let w = rect.w as usize;
+        self.draw_rows(rect, &mut |row, out| {
+            out.extend_from_slice(&pixels[row as usize * w..][..w]);
+        })
+    }
+
+    /// Draws a block of 24-bit RGB pixels, quantized with `dither`
+    fn draw_rgb888_rect(&mut self, rect: Rect, pixels: &[[u8; 3]], dither: Dither) -> Result<()> {
+        check_len(pixels.len(), rect)?;
+        let colors = dither_rgb888(rect.x, rect.y, rect.w, rect.h, pixels, dither);
+        self.draw_image_rect(rect, &colors)
+    }
+
+    fn draw_decoded_image_at(&mut self, image: &Image, x: i32, y: i32) -> Result<()> {
+        let rect = Rect::new(x, y, image.width(), image.height());
+        let dither = self.dither();
+        self.draw_rgb888_rect(rect, image.pixels(), dither)
+    }
+
+    /// Draws the visible pixels of `sprite` with its top left corner at (`x`, `y`)
+    ///
+    /// Transparent pixels are skipped, runs of visible ones are drawn as blocks.
+    fn draw_sprite(&mut self, sprite: &Sprite, x: i32, y: i32) -> Result<()> {
+        let rect = sprite.rect_at(x, y);
+        if sprite.is_opaque() {
+            return self.draw_image_rect(rect, sprite.pixels());
+        }
+        let Some(visible) = rect.intersect(self.clip()) else {
+            return Ok(());
+        };
+
+        for row in (visible.y - y) as u16..(visible.bottom() - y) as u16 {
+            let pixels = sprite.row(row);
+            for (start, end) in sprite.runs(row) {
+                let run = Rect::new(x + start as i32, y + row as i32, end - start, 1);
+                self.draw_image_rect(run, &pixels[start as usize..end as usize])?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fills `rect` with a linear gradient, quantized with `dither`
+    fn fill_gradient_rect(
+        &mut self,
+        rect: Rect,
+        from: Color,
+        to: Color,
+        direction: GradientDirection,
+        dither: Dither,
+    ) -> Result<()> {
+        let mut ditherer = Ditherer::new(rect.w, dither);
+        let mut rgb = Vec::with_capacity(rect.w as usize);
+
+        self.draw_rows(rect, &mut |row, out| {
+            rgb.clear();
+            fill::linear_gradient_row(from, to, direction, rect.w, rect.h, row, &mut rgb);
+            ditherer.dither_row(rect.x, rect.y + row as i32, &rgb, out);
+        })
+    }
+
+    /// Fills `rect` with a radial gradient from `inner` at its center to `outer` at half
+    /// its shorter side, quantized with `dither`
+    fn fill_radial_gradient_rect(
+        &mut self,
+        rect: Rect,
+        inner: Color,
+        outer: Color,
+        dither: Dither,
+    ) -> Result<()> {
+        let mut ditherer = Ditherer::new(rect.w, dither);
+        let mut rgb = Vec::with_capacity(rect.w as usize);
+
+        self.draw_rows(rect, &mut |row, out| {
+            rgb.clear();
+            fill::radial_gradient_row(inner, outer, rect.w, rect.h, row, &mut rgb);
+            ditherer.dither_row(rect.x, rect.y + row as i32, &rgb, out);
+        })
+    }
+
+    /// Fills `rect` with a checkerboard or hatch pattern
+    fn fill_pattern_rect(&mut self, rect: Rect, pattern: Pattern) -> Result<()> {
+        self.draw_rows(rect, &mut |row, out| pattern.row(rect.w, row, out))
+    }
+
+    /// Composites `color` with coverage `alpha` over the pixel at (`x`, `y`)
+    ///
+    /// Where pixels can't be read back, coverage of at least half is drawn solid.
+    fn blend_pixel(&mut self, x: i32, y: i32, color: Color, alpha: u8) -> Result<()> {
+        if alpha == 0 || !self.clip().contains(x, y) {
+            return Ok(());
+        }
+        let blended = match self.pixel(x, y) {
+            Some(under) => under.blend(color, alpha),
+            None if alpha >= 128 => color,
+            None => return Ok(()),
+        };
+        self.fill_rect(Rect::new(x, y, 1, 1), blended)
+    }
+
+    /// Draws `color` with per pixel coverage, e.g. a rasterized glyph
+    ///
+    /// Blends against `background` if given, which is fast on any canvas, otherwise
+    /// against the pixels already there.
+    fn draw_coverage(
+        &mut self,
+        rect: Rect,
+        coverage: &[u8],
+        color: Color,
+        background: Option<Color>,
+    ) -> Result<()> {
+        check_len(coverage.len(), rect)?;
+        let w = rect.w as usize;
+
+        match background {
+            Some(background) => self.draw_rows(rect, &mut |row, out| {
+                let row = &coverage[row as usize * w..][..w];
+                out.extend(row.iter().map(|&alpha| background.blend(color, alpha)));
+            }),
+            None => {
+                for (i, &alpha) in coverage.iter().enumerate() {
+                    let (x, y) = ((i % w) as i32, (i / w) as i32);
+                    self.blend_pixel(rect.x + x, rect.y + y, color, alpha)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// One pixel wide line without anti-aliasing
+    fn draw_line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: Color) -> Result<()> {
+        let clip = self.clip();
+        if y0 == y1 {
+            let Some((x, w)) = clipped_span(x0, x1, clip.x, clip.right()) else {
+                return Ok(());
+            };
+            return self.fill_rect(Rect::new(x, y0, w, 1), color);
+        } else if x0 == x1 {
+            let Some((y, h)) = clipped_span(y0, y1, clip.y, clip.bottom()) else {
+                return Ok(());
+            };
+            return self.fill_rect(Rect::new(x0, y, 1, h), color);
+        }
+        antialias::bresenham(x0, y0, x1, y1, clip, |x, y| {
+            self.fill_rect(Rect::new(x, y, 1, 1), color)
+        })
+    }
+
+    /// Anti-aliased one pixel wide line (Xiaolin Wu)
+    fn draw_line_aa(&mut self, x0: f32, y0: f32, x1: f32, y1: f32, color: Color) -> Result<()> {
+        // Only the part near the clip, the margin keeps the end pixels of a cut line
+        // outside it
+        let (from, to) = ((x0 as f64, y0 as f64), (x1 as f64, y1 as f64));
+        let Some((enter, leave)) = antialias::clip_segment(from, to, self.clip(), 2.0) else {
+            return Ok(());
+        };
+        let at = |t: f64| {
+            let (x, y) = (from.0 + t * (to.0 - from.0), from.1 + t * (to.1 - from.1));
+            (x as f32, y as f32)
+        };
+        let ((x0, y0), (x1, y1)) = (at(enter), at(leave));
+        antialias::wu_line(x0, y0, x1, y1, |x, y, alpha| self.blend_pixel(x, y, color, alpha))
+    }
+
+    /// Anti-aliased line of the given `thickness` with round caps
+    fn draw_thick_line_aa(
+        &mut self,
+        x0: f32,
+        y0: f32,
+        x1: f32,
+        y1: f32,
+        thickness: f32,
+        color: Color,
+    ) -> Result<()> {
+        let segment = antialias::Segment::new(x0, y0, x1, y1, thickness);
+        antialias::for_each_pixel(segment.bounds(), self.clip(), |x, y, px, py| {
+            self.blend_pixel(x, y, color, segment.coverage(px, py))
+        })
+    }
+
+    /// Anti-aliased circle outline of the given stroke `thickness`
+    fn draw_circle_aa(
+        &mut self,
+        cx: f32,
+        cy: f32,
+        radius: f32,
+        thickness: f32,
+        color: Color,
+    ) -> Result<()> {
+        self.draw_arc_aa(cx, cy, radius, thickness, 0.0, 360.0, color)
+    }
+
+    /// Anti-aliased filled circle
+    fn fill_circle_aa(&mut self, cx: f32, cy: f32, radius: f32, color: Color) -> Result<()> {
+        let bounds = antialias::bounds(cx, cy, radius + 1.0);
+        antialias::for_each_pixel(bounds, self.clip(), |x, y, px, py| {
+            let distance = ((px - cx).powi(2) + (py - cy).powi(2)).sqrt();
+            let alpha = antialias::coverage(radius - distance);
+            self.blend_pixel(x, y, color, alpha)
+        })
+    }
+
+    /// One pixel wide outline just inside `rect`
+    fn draw_rect(&mut self, rect: Rect, color: Color) -> Result<()> {
+        if rect.is_empty() {
+            return Ok(());
+        }
+        let (right, bottom) = (rect.right() - 1, rect.bottom() - 1);
+        self.fill_rect(Rect::new(rect.x, rect.y, rect.w, 1), color)?;
+        self.fill_rect(Rect::new(rect.x, bottom, rect.w, 1), color)?;
+        self.fill_rect(Rect::new(rect.x, rect.y, 1, rect.h), color)?;
+        self.fill_rect(Rect::new(right, rect.y, 1, rect.h), color)
+    }
+
+    /// Fills `rect` with anti-aliased corners of `radius`
+    fn fill_round_rect(&mut self, rect: Rect, radius: u16, color: Color) -> Result<()> {
+        let radius = radius.min(rect.w / 2).min(rect.h / 2);
+        if radius == 0 {
+            return self.fill_rect(rect, color);
+        }
+
+        // Straight parts first, then the four corners pixel by pixel
+        let r = radius as i32;
+        self.fill_rect(Rect::new(rect.x, rect.y + r, rect.w, rect.h - 2 * radius), color)?;
+        self.fill_rect(Rect::new(rect.x + r, rect.y, rect.w - 2 * radius, radius), color)?;
+        self.fill_rect(Rect::new(rect.x + r, rect.bottom() - r, rect.w - 2 * radius, radius), color)?;
+
+        // Pixel centers are at integer coordinates, the rectangle's edges half a pixel out
+        let left = rect.x as f32 - 0.5 + radius as f32;
+        let top = rect.y as f32 - 0.5 + radius as f32;
+        let right = rect.right() as f32 - 0.5 - radius as f32;
+        let bottom = rect.bottom() as f32 - 0.5 - radius as f32;
+        let corners = [
+            (Rect::new(rect.x, rect.y, radius, radius), left, top),
+            (Rect::new(rect.right() - r, rect.y, radius, radius), right, top),
+            (Rect::new(rect.x, rect.bottom() - r, radius, radius), left, bottom),
+            (Rect::new(rect.right() - r, rect.bottom() - r, radius, radius), right, bottom),
+        ];
+        for (corner, cx, cy) in corners {
+            antialias::for_each_pixel(corner, self.clip(), |x, y, px, py| {
+                let distance = ((px - cx).powi(2) + (py - cy).powi(2)).sqrt();
+                let alpha = antialias::coverage(radius as f32 - distance);
+                self.blend_pixel(x, y, color, alpha)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Anti-aliased arc with round caps
+    ///
+    /// Angles are in degrees, 0 pointing right and increasing clockwise, `radius` is
+    /// the center of the stroke.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_arc_aa(
+        &mut self,
+        cx: f32,
+        cy: f32,
+        radius: f32,
+        thickness: f32,
+        start_angle: f32,
+        end_angle: f32,
+        color: Color,
+    ) -> Result<()> {
+        let arc = antialias::Arc::new(cx, cy, radius, thickness, start_angle, end_angle);
+        let bounds = antialias::bounds(cx, cy, radius + thickness / 2.0 + 1.0);
+        antialias::for_each_pixel(bounds, self.clip(), |x, y, px, py| {
+            self.blend_pixel(x, y, color, arc.coverage(px, py))
+        })
+    }
+
+    /// Draws text with the top left corner of its first line at (`x`, `y`)
+    ///
+    /// Glyphs are blended like `draw_coverage`, against `background` if given. Lines
+    /// are separated by `\n`.
+    fn draw_text(
+        &mut self,
+        font: &dyn Font,
+        text: &str,
+        x: i32,
+        y: i32,
+        color: Color,
+        background: Option<Color>,
+    ) -> Result<()> {
+        let metrics = font.line_metrics();
+        for (index, line) in text.lines().enumerate() {
+            let baseline = y + metrics.ascent + index as i32 * metrics.line_height;
+            self.draw_text_line(font, line, x as f32, baseline, color, background)?;
+        }
+        Ok(())
+    }
+
+    /// Draws text word wrapped to the width of `rect`, aligned and clipped to it
+    ///
+    /// The box itself is not filled.
+    fn draw_text_box(
+        &mut self,
+        font: &dyn Font,
+        text: &str,
+        rect: Rect,
+        align: Align,
+        color: Color,
+        background: Option<Color>,
+    ) -> Result<()> {
+        let metrics = font.line_metrics();
+        self.push_clip(rect);
+        let result = text::wrap(font, text, rect.w)
+            .into_iter()
+            .enumerate()
+            .try_for_each(|(index, line)| {
+                let baseline = rect.y + metrics.ascent + index as i32 * metrics.line_height;
+                let x = text::align_x(align, rect.x, rect.w, text::line_width(font, line));
+                self.draw_text_line(font, line, x, baseline, color, background)
+            });
+        self.pop_clip();
+        result
+    }
+
+    /// Draws a single line of text starting at pen position `x` on `baseline`
+    fn draw_text_line(
+        &mut self,
+        font: &dyn Font,
+        line: &str,
+        x: f32,
+        baseline: i32,
+        color: Color,
+        background: Option<Color>,
+    ) -> Result<()> {
+        text::layout_line(font, line, x, |pen, glyph| {
+            let rect = Rect::new(
+                pen.round() as i32 + glyph.x_offset,
+                baseline + glyph.y_offset,
+                glyph.width,
+                glyph.height,
+            );
+            if rect.is_empty() || rect.intersect(self.clip()).is_none() {
+                return Ok(());
+            }
+            self.draw_coverage(rect, &glyph.coverage, color, background)
+        })
+    }
+}
+
+fn check_len(given: usize, rect: Rect) -> Result<()> {
+    let expected = rect.w as usize * rect.h as usize;
+    if given != expected {
+        return Err(Error::Buffer { given, expected });
+    }
+    Ok(())
+}
+
+/// Start and length of the pixels from `a` to `b`, both included, within `from..to`
+///
+/// Clamped before converting, so spans far beyond the canvas don't overflow.
+fn clipped_span(a: i32, b: i32, from: i32, to: i32) -> Option<(i32, u16)> {
+    let start = a.min(b).max(from);
+    let end = a.max(b).min(to - 1);
+    (start <= end).then(|| (start, (end - start + 1) as u16))
+}
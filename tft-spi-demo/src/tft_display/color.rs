@@ -1,9 +1,30 @@
-use std::fmt::Display;
+use std::{error, fmt::Display, str::FromStr};
+
+use crate::tft_display::palette;
 
 /// 18-bit RGB color
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Color(u8, u8, u8);
 
+#[derive(Debug, PartialEq)]
+pub enum ColorError {
+    OutOfRange(u32),
+    InvalidHex(String),
+    UnknownName(String),
+}
+
+impl Display for ColorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OutOfRange(color) => write!(f, "Color {color:#X} exceeds 0xFFFFFF"),
+            Self::InvalidHex(hex) => write!(f, "Invalid hex color: {hex}"),
+            Self::UnknownName(name) => write!(f, "Unknown color name: {name}"),
+        }
+    }
+}
+
+impl error::Error for ColorError {}
+
 impl Color {
     pub const BLACK: Color = Color(0x00, 0x00, 0x00);
     pub const WHITE: Color = Color(0x3F, 0x3F, 0x3F);
@@ -25,6 +46,11 @@ impl Color {
     //     )
     // }
 
+    /// Builds a color from the panel's native 6-bit channels, extra bits are dropped
+    pub const fn from_six_bit_rgb(r: u8, g: u8, b: u8) -> Self {
+        Self(r & 0x3F, g & 0x3F, b & 0x3F)
+    }
+
     pub fn from_eight_bit_rgb(r: u8, g: u8, b: u8) -> Self {
         Self(
             Self::eight_to_six_bit(r),
@@ -37,10 +63,10 @@ impl Color {
     ///
     /// # Errors
     ///
-    /// Errors if input is greater than 0xFFFFFF ()
-    pub fn from_24_bit(color: u32) -> Result<Self, &'static str> {
+    /// Errors if input is greater than 0xFFFFFF
+    pub fn from_24_bit(color: u32) -> Result<Self, ColorError> {
         if color > 0xFF_FF_FF {
-            return Err("color input too large");
+            return Err(ColorError::OutOfRange(color));
         }
 
         Ok(Self::from_eight_bit_rgb(
@@ -50,6 +76,121 @@ impl Color {
         ))
     }
 
+    /// Converts 16-bit RGB565 (e.g. from a 16-bit framebuffer) to `Color`
+    pub const fn from_rgb565(color: u16) -> Self {
+        let r = (color >> 11) as u8 & 0x1F;
+        let g = (color >> 5) as u8 & 0x3F;
+        let b = color as u8 & 0x1F;
+
+        // Repeat the top bit to cover the full 6-bit range
+        Self((r << 1) | (r >> 4), g, (b << 1) | (b >> 4))
+    }
+
+    pub const fn to_rgb565(self) -> u16 {
+        ((self.0 as u16 >> 1) << 11) | ((self.1 as u16) << 5) | (self.2 as u16 >> 1)
+    }
+
+    /// 8-bit channels, the 6-bit value's top bits repeated into the low bits
+    pub const fn to_rgb888(self) -> [u8; 3] {
+        [
+            Self::six_to_eight_bit(self.0),
+            Self::six_to_eight_bit(self.1),
+            Self::six_to_eight_bit(self.2),
+        ]
+    }
+
+    pub const fn to_24_bit(self) -> u32 {
+        let [r, g, b] = self.to_rgb888();
+        ((r as u32) << 16) | ((g as u32) << 8) | b as u32
+    }
+
+    /// Parses `#RRGGBB` or `#RGB`, the `#` being optional
+    pub fn from_hex(hex: &str) -> Result<Self, ColorError> {
+        let invalid = || ColorError::InvalidHex(hex.to_string());
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        let value = u32::from_str_radix(digits, 16).map_err(|_| invalid())?;
+        match digits.len() {
+            6 => Self::from_24_bit(value),
+            3 => {
+                let expand = |nibble: u32| (nibble & 0xF) as u8 * 0x11;
+                Ok(Self::from_eight_bit_rgb(
+                    expand(value >> 8),
+                    expand(value >> 4),
+                    expand(value),
+                ))
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Looks up a CSS color name such as `cornflowerblue`, ignoring case
+    pub fn from_name(name: &str) -> Result<Self, ColorError> {
+        palette::lookup(name)
+            .ok_or_else(|| ColorError::UnknownName(name.to_string()))
+            .and_then(Self::from_24_bit)
+    }
+
+    /// `hue` in degrees, `saturation` and `value` in 0.0..=1.0
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let value = value.clamp(0.0, 1.0);
+
+        let chroma = value * saturation;
+        Self::from_hue_chroma(hue, chroma, value - chroma)
+    }
+
+    /// Returns (hue in degrees, saturation, value)
+    pub fn to_hsv(self) -> (f32, f32, f32) {
+        let (hue, min, max) = self.hue_min_max();
+        let saturation = if max > 0.0 { (max - min) / max } else { 0.0 };
+        (hue, saturation, max)
+    }
+
+    /// `hue` in degrees, `saturation` and `lightness` in 0.0..=1.0
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        Self::from_hue_chroma(hue, chroma, lightness - chroma / 2.0)
+    }
+
+    /// Returns (hue in degrees, saturation, lightness)
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let (hue, min, max) = self.hue_min_max();
+        let lightness = (max + min) / 2.0;
+        let saturation = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+        (hue, saturation, lightness)
+    }
+
+    /// Linear interpolation, `t` = 0.0 gives `self` and 1.0 gives `other`
+    pub fn lerp(self, other: Color, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Self(mix(self.0, other.0), mix(self.1, other.1), mix(self.2, other.2))
+    }
+
+    /// Composites `foreground` with coverage `alpha` (0 transparent, 255 opaque) over `self`
+    pub fn blend(self, foreground: Color, alpha: u8) -> Self {
+        let alpha = alpha as u16;
+        let mix = |back: u8, front: u8| {
+            ((front as u16 * alpha + back as u16 * (255 - alpha) + 127) / 255) as u8
+        };
+        Self(
+            mix(self.0, foreground.0),
+            mix(self.1, foreground.1),
+            mix(self.2, foreground.2),
+        )
+    }
+
     pub fn red(&self) -> u8 {
         self.0
     }
@@ -62,8 +203,62 @@ impl Color {
         self.2
     }
 
+    /// Rounds to the nearest 6-bit level, see `dither` for smooth gradients
     fn eight_to_six_bit(input: u8) -> u8 {
-        ((input as f32 / 255.0) * 63.0) as u8
+        ((input as u16 * 63 + 127) / 255) as u8
+    }
+
+    const fn six_to_eight_bit(input: u8) -> u8 {
+        (input << 2) | (input >> 4)
+    }
+
+    fn from_hue_chroma(hue: f32, chroma: f32, min: f32) -> Self {
+        let sector = hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+
+        let (r, g, b) = match sector as u8 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let to_six_bit = |c: f32| ((c + min) * 63.0).round().clamp(0.0, 63.0) as u8;
+        Self(to_six_bit(r), to_six_bit(g), to_six_bit(b))
+    }
+
+    /// Hue in degrees plus the smallest and largest channel in 0.0..=1.0
+    fn hue_min_max(self) -> (f32, f32, f32) {
+        let (r, g, b) = (self.0 as f32 / 63.0, self.1 as f32 / 63.0, self.2 as f32 / 63.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+
+        let hue = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        (hue, min, max)
+    }
+}
+
+/// Parses a hex color (`#RRGGBB`, `#RGB`) or a CSS color name
+impl FromStr for Color {
+    type Err = ColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.starts_with('#') {
+            Self::from_hex(s)
+        } else {
+            Self::from_name(s).or_else(|err| Self::from_hex(s).map_err(|_| err))
+        }
     }
 }
 
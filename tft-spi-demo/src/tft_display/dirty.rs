@@ -0,0 +1,70 @@
+use crate::tft_display::geometry::Rect;
+
+/// More rectangles than this are merged into their bounding box, past that point
+/// one large transfer beats many small ones
+const MAX_RECTS: usize = 8;
+
+/// Set of changed screen areas, merged so each pixel is sent at most once
+#[derive(Clone, Debug, Default)]
+pub struct DirtyRegion {
+    rects: Vec<Rect>,
+}
+
+impl DirtyRegion {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Marks `rect` as changed, merging it with the rectangles it overlaps or touches
+    pub fn add(&mut self, rect: Rect) {
+        if rect.is_empty() {
+            return;
+        }
+
+        let mut rect = rect;
+        // A merged rectangle may reach others, repeat until nothing overlaps
+        while let Some(index) = self.rects.iter().position(|other| touches(*other, rect)) {
+            rect = rect.union(self.rects.swap_remove(index));
+        }
+        self.rects.push(rect);
+
+        if self.rects.len() > MAX_RECTS {
+            let bounds = self.bounds();
+            self.rects.clear();
+            self.rects.extend(bounds);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    pub fn rects(&self) -> &[Rect] {
+        &self.rects
+    }
+
+    /// Bounding box of everything changed
+    pub fn bounds(&self) -> Option<Rect> {
+        self.rects
+            .iter()
+            .copied()
+            .reduce(|bounds, rect| bounds.union(rect))
+    }
+
+    /// Removes and returns the changed rectangles, each clipped to `clip`
+    pub fn take(&mut self, clip: Rect) -> Vec<Rect> {
+        self.rects
+            .drain(..)
+            .filter_map(|rect| rect.intersect(clip))
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.rects.clear();
+    }
+}
+
+/// Overlapping or sharing an edge
+fn touches(a: Rect, b: Rect) -> bool {
+    a.x <= b.right() && b.x <= a.right() && a.y <= b.bottom() && b.y <= a.bottom()
+}
@@ -0,0 +1,109 @@
+use crate::tft_display::color::Color;
+
+/// How 8-bit channels are reduced to the panel's 6 bits
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Dither {
+    /// Plain rounding, bands on smooth gradients
+    #[default]
+    None,
+    /// 4x4 Bayer matrix, stable between frames and cheap
+    Ordered,
+    /// Floyd–Steinberg error diffusion, best for photos
+    FloydSteinberg,
+}
+
+/// 4x4 Bayer threshold matrix
+const BAYER_4X4: [[u8; 4]; 4] = [
+    [0, 8, 2, 10],
+    [12, 4, 14, 6],
+    [3, 11, 1, 9],
+    [15, 7, 13, 5],
+];
+
+/// Distance between two 6-bit levels on the 8-bit scale
+const STEP: f32 = 255.0 / 63.0;
+
+/// Quantizes a `w` x `h` block of 8-bit RGB pixels to `Color`
+///
+/// `x` and `y` give the block's screen position, so ordered dithering stays aligned
+/// when neighbouring blocks are drawn separately.
+pub fn dither_rgb888(x: i32, y: i32, w: u16, h: u16, pixels: &[[u8; 3]], dither: Dither) -> Vec<Color> {
+    let mut ditherer = Ditherer::new(w, dither);
+    let mut colors = Vec::with_capacity(pixels.len());
+
+    for (row, line) in pixels.chunks_exact(w.max(1) as usize).take(h as usize).enumerate() {
+        ditherer.dither_row(x, y + row as i32, line, &mut colors);
+    }
+    colors
+}
+
+/// Row by row quantizer, carrying the diffused error between rows
+pub struct Ditherer {
+    dither: Dither,
+    /// Floyd–Steinberg error for the current and next row, per channel
+    errors: [Vec<[f32; 3]>; 2],
+}
+
+impl Ditherer {
+    pub fn new(width: u16, dither: Dither) -> Self {
+        let len = match dither {
+            // One extra entry on each side saves bounds checks
+            Dither::FloydSteinberg => width as usize + 2,
+            _ => 0,
+        };
+        Self {
+            dither,
+            errors: [vec![[0.0; 3]; len], vec![[0.0; 3]; len]],
+        }
+    }
+
+    /// Quantizes one row starting at screen position (`x`, `y`), appending to `out`
+    pub fn dither_row(&mut self, x: i32, y: i32, row: &[[u8; 3]], out: &mut Vec<Color>) {
+        match self.dither {
+            Dither::None => out.extend(
+                row.iter()
+                    .map(|&[r, g, b]| Color::from_eight_bit_rgb(r, g, b)),
+            ),
+            Dither::Ordered => {
+                let thresholds = &BAYER_4X4[y.rem_euclid(4) as usize];
+                let x = x.rem_euclid(4) as usize;
+                out.extend(row.iter().enumerate().map(|(i, rgb)| {
+                    // Offset in (-0.5, 0.5) of a level
+                    let threshold = thresholds[(x + i) % 4] as f32 / 16.0 - 15.0 / 32.0;
+                    let [r, g, b] = rgb.map(|c| quantize(c as f32 + threshold * STEP));
+                    Color::from_six_bit_rgb(r, g, b)
+                }));
+            }
+            Dither::FloydSteinberg => self.diffuse_row(row, out),
+        }
+    }
+
+    fn diffuse_row(&mut self, row: &[[u8; 3]], out: &mut Vec<Color>) {
+        let [current, next] = &mut self.errors;
+        let width = row.len().min(current.len().saturating_sub(2));
+
+        for (i, rgb) in row.iter().take(width).enumerate() {
+            let mut color = [0; 3];
+            for c in 0..3 {
+                let wanted = rgb[c] as f32 + current[i + 1][c];
+                let level = quantize(wanted);
+                let error = wanted - level as f32 * STEP;
+                color[c] = level;
+
+                current[i + 2][c] += error * 7.0 / 16.0;
+                next[i][c] += error * 3.0 / 16.0;
+                next[i + 1][c] += error * 5.0 / 16.0;
+                next[i + 2][c] += error * 1.0 / 16.0;
+            }
+            out.push(Color::from_six_bit_rgb(color[0], color[1], color[2]));
+        }
+
+        std::mem::swap(current, next);
+        next.iter_mut().for_each(|error| *error = [0.0; 3]);
+    }
+}
+
+/// Nearest 6-bit level of an 8-bit value
+fn quantize(value: f32) -> u8 {
+    (value / STEP).round().clamp(0.0, 63.0) as u8
+}
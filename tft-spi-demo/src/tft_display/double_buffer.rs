@@ -0,0 +1,87 @@
+use crate::tft_display::{
+    canvas::Canvas, color::Color, framebuffer::Framebuffer, geometry::Rect, tft_display::Result,
+    tft_display::TftDisplay,
+};
+
+/// Screen sized back buffer drawn into, plus a copy of what the panel shows
+///
+/// `present` sends only the area that differs from the shown frame, timed to start
+/// right after vertical blank when the display has a `VSync`.
+pub struct DoubleBuffer {
+    back: Framebuffer,
+    front: Framebuffer,
+    /// Front buffer doesn't match the panel, e.g. before the first frame
+    stale: bool,
+}
+
+impl DoubleBuffer {
+    pub fn new(width: u16, height: u16, color: Color) -> Self {
+        Self {
+            back: Framebuffer::new(width, height, color),
+            front: Framebuffer::new(width, height, color),
+            stale: true,
+        }
+    }
+
+    /// Buffer for the whole screen of `display` in its current orientation
+    pub fn for_display(display: &TftDisplay, color: Color) -> Self {
+        Self::new(display.width(), display.height(), color)
+    }
+
+    /// The buffer to draw the next frame into
+    pub fn back(&mut self) -> &mut Framebuffer {
+        &mut self.back
+    }
+
+    /// The frame last sent to the panel
+    pub fn front(&self) -> &Framebuffer {
+        &self.front
+    }
+
+    /// Sends the whole frame on the next `present`, e.g. after the panel was reset
+    pub fn invalidate(&mut self) {
+        self.stale = true;
+    }
+
+    /// Area of the back buffer that differs from the shown frame
+    pub fn changed(&self) -> Option<Rect> {
+        if self.stale {
+            return Some(self.back.bounds());
+        }
+
+        let (w, h) = (self.back.width(), self.back.height());
+        let mut changed: Option<Rect> = None;
+        for y in 0..h {
+            let (back, front) = (self.back.row(0, y, w), self.front.row(0, y, w));
+            let Some(first) = back.iter().zip(front).position(|(b, f)| b != f) else {
+                continue;
+            };
+            let last = back
+                .iter()
+                .zip(front)
+                .rposition(|(b, f)| b != f)
+                .unwrap_or(first);
+            let row = Rect::new(first as i32, y as i32, (last - first + 1) as u16, 1);
+            changed = Some(changed.map_or(row, |changed| changed.union(row)));
+        }
+        changed
+    }
+
+    /// Waits for vertical blank and sends what changed since the last frame
+    ///
+    /// Returns the area sent. The back buffer keeps its content, so frames can be
+    /// drawn incrementally.
+    pub fn present(&mut self, display: &mut TftDisplay) -> Result<Option<Rect>> {
+        let Some(changed) = self.changed() else {
+            return Ok(None);
+        };
+
+        display.wait_for_vblank()?;
+        display.flush_rect(&self.back, changed)?;
+
+        self.front
+            .copy_from(&self.back, changed, changed.x, changed.y);
+        self.stale = false;
+        Ok(Some(changed))
+    }
+}
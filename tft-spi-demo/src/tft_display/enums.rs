@@ -8,7 +8,7 @@ pub enum Command {
     // PartialModeOn = 0x12, // partial mode
     NormalDisplayModeOn = 0x13, // normal display
     DisplayInversionOff = 0x20, // display invert off
-    // DisplayInversionOn = 0x21, // display invert on
+    DisplayInversionOn = 0x21, // display invert on
     // DisplayOff = 0x28, // display off
     DisplayOn = 0x29, // display on
     // IdleModeOn = 0x39, // idle mode on
@@ -18,10 +18,12 @@ pub enum Command {
     MemoryWrite = 0x2C,      // memory write
     // MemoryRead = 0x2E, // memory read
     // PartialArea = 0x30, // partial area
-    // VerticalScrollingDefinition = 0x33, // vertical scroll def
+    VerticalScrollingDefinition = 0x33, // vertical scroll def
+    TearingEffectLineOff = 0x34, // tearing effect line off
+    TearingEffectLineOn = 0x35,  // tearing effect line on
     InterfacePixelFormat = 0x3A, // interface pixel format
     MemoryAccessControl = 0x36,  // memory access control
-    // VerticalScrollingStartAddress = 0x37, //vertical scrolling start address
+    VerticalScrollingStartAddress = 0x37, //vertical scrolling start address
 
     // frame rate control
     InterfaceModeControl = 0xB0,
@@ -47,6 +49,7 @@ pub enum Command {
 }
 
 #[repr(u8)]
+#[derive(Clone, Copy)]
 pub enum MadControl {
     MadctlMy = 0x80,
     MadctlMx = 0x40,
@@ -67,7 +70,7 @@ pub enum Mode {
     DisplayOff,
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Rotate {
     Degrees0,
     Degrees90,
@@ -75,6 +78,41 @@ pub enum Rotate {
     Degrees270,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorOrder {
+    Rgb,
+    Bgr,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GradientDirection {
+    /// Left to right
+    Horizontal,
+    /// Top to bottom
+    Vertical,
+    /// Top left to bottom right
+    Diagonal,
+}
+
+/// When the controller pulses its TE output, the parameter of `TearingEffectLineOn`
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TearingMode {
+    /// Once per frame, during vertical blanking
+    VBlank = 0x00,
+    /// During vertical and every horizontal blanking
+    VHBlank = 0x01,
+}
+
+/// Horizontal placement of text lines within their box
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
 #[derive(PartialEq)]
 pub enum PcbType {
     Red,
@@ -82,3 +120,13 @@ pub enum PcbType {
     Black,
     None,
 }
+
+impl PcbType {
+    /// Order the panel's subpixels are wired in, RGB on the black PCB
+    pub fn color_order(&self) -> ColorOrder {
+        match self {
+            PcbType::Black => ColorOrder::Rgb,
+            _ => ColorOrder::Bgr,
+        }
+    }
+}
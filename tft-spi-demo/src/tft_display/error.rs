@@ -1,18 +1,36 @@
-use std::{error, fmt::Display};
+use std::{error, fmt::Display, io};
 
-use rppal::spi;
+use rppal::{gpio, spi};
+
+use crate::tft_display::color::ColorError;
 
 #[derive(Debug)]
 pub enum Error {
     Spi(spi::Error),
+    Gpio(gpio::Error),
     Size { given: u16, max: u16 },
+    Gamma { index: usize, value: u8, max: u8 },
+    Buffer { given: usize, expected: usize },
+    Io(io::Error),
+    Decode(String),
+    Color(ColorError),
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Spi(err) => write!(f, "SPI Error: {err}"),
+            Self::Gpio(err) => write!(f, "GPIO Error: {err}"),
             Self::Size { given, max } => write!(f, "Given size: {given}, Max size: {max}"),
+            Self::Gamma { index, value, max } => {
+                write!(f, "Gamma parameter {index}: {value:#04X} exceeds mask {max:#04X}")
+            }
+            Self::Buffer { given, expected } => {
+                write!(f, "Given buffer length: {given}, Expected length: {expected}")
+            }
+            Self::Io(err) => write!(f, "IO Error: {err}"),
+            Self::Decode(msg) => write!(f, "Decode Error: {msg}"),
+            Self::Color(err) => write!(f, "Color Error: {err}"),
         }
     }
 }
@@ -24,3 +42,45 @@ impl From<spi::Error> for Error {
         Error::Spi(err)
     }
 }
+
+impl From<gpio::Error> for Error {
+    fn from(err: gpio::Error) -> Self {
+        Error::Gpio(err)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<ColorError> for Error {
+    fn from(err: ColorError) -> Self {
+        Error::Color(err)
+    }
+}
+
+impl From<png::DecodingError> for Error {
+    fn from(err: png::DecodingError) -> Self {
+        Error::Decode(err.to_string())
+    }
+}
+
+impl From<qoi::Error> for Error {
+    fn from(err: qoi::Error) -> Self {
+        Error::Decode(err.to_string())
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(err: toml::de::Error) -> Self {
+        Error::Decode(err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Decode(err.to_string())
+    }
+}
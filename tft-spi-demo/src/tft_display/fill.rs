@@ -0,0 +1,136 @@
+use crate::tft_display::{color::Color, enums::GradientDirection};
+
+/// Repeating two-color fill, positioned relative to the filled rectangle
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pattern {
+    Checkerboard {
+        size: u16,
+        even: Color,
+        odd: Color,
+    },
+    Hatch {
+        style: Hatch,
+        spacing: u16,
+        thickness: u16,
+        foreground: Color,
+        background: Color,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Hatch {
+    Horizontal,
+    Vertical,
+    /// Lines from bottom left to top right
+    Diagonal,
+    /// Lines from top left to bottom right
+    AntiDiagonal,
+    Cross,
+    DiagonalCross,
+}
+
+impl Pattern {
+    pub fn color_at(&self, x: u16, y: u16) -> Color {
+        match *self {
+            Pattern::Checkerboard { size, even, odd } => {
+                let size = size.max(1);
+                if (x / size + y / size).is_multiple_of(2) {
+                    even
+                } else {
+                    odd
+                }
+            }
+            Pattern::Hatch {
+                style,
+                spacing,
+                thickness,
+                foreground,
+                background,
+            } => {
+                let spacing = spacing.max(1) as u32;
+                let on_line = |offset: u32| offset % spacing < thickness as u32;
+                let (x, y) = (x as u32, y as u32);
+                // Offset keeps the anti-diagonal positive
+                let anti = x + spacing * (1 + y / spacing) - y;
+
+                let hit = match style {
+                    Hatch::Horizontal => on_line(y),
+                    Hatch::Vertical => on_line(x),
+                    Hatch::Diagonal => on_line(x + y),
+                    Hatch::AntiDiagonal => on_line(anti),
+                    Hatch::Cross => on_line(x) || on_line(y),
+                    Hatch::DiagonalCross => on_line(x + y) || on_line(anti),
+                };
+                if hit {
+                    foreground
+                } else {
+                    background
+                }
+            }
+        }
+    }
+
+    /// Appends row `y` of a `w` pixel wide fill
+    pub fn row(&self, w: u16, y: u16, out: &mut Vec<Color>) {
+        out.extend((0..w).map(|x| self.color_at(x, y)));
+    }
+}
+
+/// Appends row `y` of a `w` x `h` linear gradient in 8-bit RGB
+pub fn linear_gradient_row(
+    from: Color,
+    to: Color,
+    direction: GradientDirection,
+    w: u16,
+    h: u16,
+    y: u16,
+    out: &mut Vec<[u8; 3]>,
+) {
+    let position = |x: u16| match direction {
+        GradientDirection::Horizontal => fraction(x, w),
+        GradientDirection::Vertical => fraction(y, h),
+        GradientDirection::Diagonal => (fraction(x, w) + fraction(y, h)) / 2.0,
+    };
+
+    let (from, to) = (from.to_rgb888(), to.to_rgb888());
+    match direction {
+        GradientDirection::Vertical => {
+            let color = mix(from, to, position(0));
+            out.extend((0..w).map(|_| color));
+        }
+        _ => out.extend((0..w).map(|x| mix(from, to, position(x)))),
+    }
+}
+
+/// Appends row `y` of a radial gradient centered in a `w` x `h` rectangle
+///
+/// The gradient reaches `outer` at half the shorter side and stays there beyond.
+pub fn radial_gradient_row(inner: Color, outer: Color, w: u16, h: u16, y: u16, out: &mut Vec<[u8; 3]>) {
+    let (inner, outer) = (inner.to_rgb888(), outer.to_rgb888());
+    let radius = (w.min(h) as f32 / 2.0).max(1.0);
+    let (cx, cy) = (w as f32 / 2.0, h as f32 / 2.0);
+    let dy = y as f32 + 0.5 - cy;
+
+    out.extend((0..w).map(|x| {
+        let dx = x as f32 + 0.5 - cx;
+        mix(inner, outer, (dx * dx + dy * dy).sqrt() / radius)
+    }));
+}
+
+/// Position of pixel `i` between the first (0.0) and last (1.0) of `len`
+fn fraction(i: u16, len: u16) -> f32 {
+    if len <= 1 {
+        0.0
+    } else {
+        i as f32 / (len - 1) as f32
+    }
+}
+
+fn mix(from: [u8; 3], to: [u8; 3], t: f32) -> [u8; 3] {
+    let t = t.clamp(0.0, 1.0);
+    let mut out = [0; 3];
+    for (i, channel) in out.iter_mut().enumerate() {
+        *channel = (from[i] as f32 + (to[i] as f32 - from[i] as f32) * t).round() as u8;
+    }
+    out
+}
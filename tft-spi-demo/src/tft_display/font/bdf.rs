@@ -0,0 +1,132 @@
+use std::{collections::HashMap, str::SplitWhitespace};
+
+use crate::tft_display::{
+    error::Error,
+    font::{bitmap::BitmapFont, Glyph, LineMetrics},
+    tft_display::Result,
+};
+
+/// Parses a BDF (Glyph Bitmap Distribution Format) font
+///
+/// Encodings are taken as Unicode code points, which holds for ISO 10646 and
+/// ISO 8859-1 fonts. Glyphs without a standard encoding are skipped.
+pub fn parse(source: &str) -> Result<BitmapFont> {
+    let mut lines = source.lines().enumerate();
+    let mut glyphs = HashMap::new();
+    // Width, height and offsets of the font's bounding box
+    let mut bounding_box = [0; 4];
+    let mut advance = None;
+    let mut ascent = None;
+    let mut descent = None;
+    let mut default_char = None;
+
+    while let Some((index, line)) = lines.next() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("FONTBOUNDINGBOX") => bounding_box = numbers(&mut words, index)?,
+            Some("DWIDTH") => advance = Some(numbers::<2>(&mut words, index)?[0]),
+            Some("FONT_ASCENT") => ascent = Some(numbers::<1>(&mut words, index)?[0]),
+            Some("FONT_DESCENT") => descent = Some(numbers::<1>(&mut words, index)?[0]),
+            Some("DEFAULT_CHAR") => {
+                default_char = char::from_u32(numbers::<1>(&mut words, index)?[0] as u32)
+            }
+            Some("STARTCHAR") => {
+                let advance = advance.unwrap_or(bounding_box[0]);
+                if let (Some(c), glyph) = parse_char(&mut lines, bounding_box, advance)? {
+                    glyphs.insert(c, glyph);
+                }
+            }
+            Some("ENDFONT") => break,
+            _ => {}
+        }
+    }
+
+    let [_, height, _, y_offset] = bounding_box;
+    let ascent = ascent.unwrap_or(height + y_offset);
+    let descent = descent.unwrap_or(-y_offset);
+    let metrics = LineMetrics {
+        ascent,
+        descent,
+        line_height: ascent + descent,
+    };
+    Ok(BitmapFont::new(glyphs, metrics, default_char))
+}
+
+/// Parses one glyph up to its ENDCHAR
+fn parse_char<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    bounding_box: [i32; 4],
+    advance: i32,
+) -> Result<(Option<char>, Glyph)> {
+    let mut c = None;
+    let mut advance = advance;
+    let [mut width, mut height, mut x_offset, mut y_offset] = bounding_box;
+    let mut coverage = Vec::new();
+
+    while let Some((index, line)) = lines.next() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            // -1 marks glyphs outside the font's encoding
+            Some("ENCODING") => c = char::from_u32(numbers::<1>(&mut words, index)?[0] as u32),
+            Some("DWIDTH") => advance = numbers::<2>(&mut words, index)?[0],
+            Some("BBX") => [width, height, x_offset, y_offset] = numbers(&mut words, index)?,
+            Some("BITMAP") => {
+                if width < 0 || height < 0 {
+                    return Err(decode_error(index, "negative glyph size"));
+                }
+                coverage.reserve(width as usize * height as usize);
+                for _ in 0..height {
+                    let (index, row) = lines
+                        .next()
+                        .ok_or_else(|| decode_error(index, "bitmap ends early"))?;
+                    bitmap_row(row.trim(), width as usize, &mut coverage)
+                        .ok_or_else(|| decode_error(index, "invalid bitmap row"))?;
+                }
+            }
+            Some("ENDCHAR") => {
+                // Pad glyphs whose BITMAP is missing
+                coverage.resize(width.max(0) as usize * height.max(0) as usize, 0);
+                let glyph = Glyph {
+                    width: width as u16,
+                    height: height as u16,
+                    x_offset,
+                    y_offset: -(y_offset + height),
+                    advance: advance as f32,
+                    coverage,
+                };
+                return Ok((c, glyph));
+            }
+            _ => {}
+        }
+    }
+    Err(Error::Decode("BDF glyph without ENDCHAR".to_string()))
+}
+
+/// Appends the coverage of a hex encoded row, most significant bit leftmost
+fn bitmap_row(row: &str, width: usize, coverage: &mut Vec<u8>) -> Option<()> {
+    let bytes = width.div_ceil(8);
+    if row.len() < bytes * 2 || !row.is_ascii() {
+        return None;
+    }
+    for byte in 0..bytes {
+        let bits = u8::from_str_radix(&row[byte * 2..byte * 2 + 2], 16).ok()?;
+        let pixels = (width - byte * 8).min(8);
+        coverage.extend((0..pixels).map(|bit| ((bits >> (7 - bit)) & 1) * 255));
+    }
+    Some(())
+}
+
+fn numbers<const N: usize>(words: &mut SplitWhitespace, index: usize) -> Result<[i32; N]> {
+    let mut numbers = [0; N];
+    for number in numbers.iter_mut() {
+        *number = words
+            .next()
+            .and_then(|word| word.parse().ok())
+            .ok_or_else(|| decode_error(index, "expected a number"))?;
+    }
+    Ok(numbers)
+}
+
+fn decode_error(index: usize, message: &str) -> Error {
+    Error::Decode(format!("BDF line {}: {message}", index + 1))
+}
@@ -0,0 +1,166 @@
+use std::{collections::HashMap, fs, path::Path, sync::Arc};
+
+use crate::tft_display::{
+    error::Error,
+    font::{bdf, builtin, pcf, Font, Glyph, LineMetrics},
+    tft_display::Result,
+};
+
+/// Font of fixed bitmaps, drawn crisp without anti-aliasing
+///
+/// Loaded from BDF or PCF files, or the built-in 5x7 font. Characters the font lacks
+/// are drawn with its fallback glyph.
+#[derive(Clone)]
+pub struct BitmapFont {
+    glyphs: HashMap<char, Arc<Glyph>>,
+    fallback: Arc<Glyph>,
+    metrics: LineMetrics,
+}
+
+impl BitmapFont {
+    /// Font from its glyphs, `default_char` is drawn for missing characters
+    ///
+    /// Without a usable `default_char` the fallback is U+FFFD, '?' or a hollow box,
+    /// whichever the font has first.
+    pub fn new(
+        glyphs: HashMap<char, Glyph>,
+        metrics: LineMetrics,
+        default_char: Option<char>,
+    ) -> Self {
+        let glyphs: HashMap<char, Arc<Glyph>> = glyphs
+            .into_iter()
+            .map(|(c, glyph)| (c, Arc::new(glyph)))
+            .collect();
+        let fallback = default_char
+            .into_iter()
+            .chain(['\u{FFFD}', '?'])
+            .find_map(|c| glyphs.get(&c).cloned())
+            .unwrap_or_else(|| Arc::new(hollow_box(&metrics)));
+
+        Self {
+            glyphs,
+            fallback,
+            metrics,
+        }
+    }
+
+    /// The 5x7 font for printable ASCII
+    pub fn builtin() -> Self {
+        Self::new(builtin::glyphs().collect(), builtin::METRICS, None)
+    }
+
+    /// Loads a BDF or PCF file, told apart by its content
+    ///
+    /// Compressed files (`.pcf.gz`) have to be unpacked first.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if pcf::is_pcf(bytes) {
+            return Self::from_pcf(bytes);
+        }
+        let source = std::str::from_utf8(bytes).map_err(|err| Error::Decode(err.to_string()))?;
+        Self::from_bdf(source)
+    }
+
+    pub fn from_bdf(source: &str) -> Result<Self> {
+        bdf::parse(source)
+    }
+
+    pub fn from_pcf(bytes: &[u8]) -> Result<Self> {
+        pcf::parse(bytes)
+    }
+
+    /// The font with every pixel drawn as a `factor` x `factor` block
+    pub fn scaled(&self, factor: u8) -> Self {
+        let factor = factor.max(1);
+        let scale = |glyph: &Glyph| Arc::new(scale_glyph(glyph, factor));
+        let metrics = LineMetrics {
+            ascent: self.metrics.ascent * factor as i32,
+            descent: self.metrics.descent * factor as i32,
+            line_height: self.metrics.line_height * factor as i32,
+        };
+
+        Self {
+            glyphs: self
+                .glyphs
+                .iter()
+                .map(|(&c, glyph)| (c, scale(glyph)))
+                .collect(),
+            fallback: scale(&self.fallback),
+            metrics,
+        }
+    }
+
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.glyphs.contains_key(&c)
+    }
+
+    /// Number of glyphs, not counting the fallback
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+}
+
+impl Font for BitmapFont {
+    fn line_metrics(&self) -> LineMetrics {
+        self.metrics
+    }
+
+    fn glyph(&self, c: char) -> Arc<Glyph> {
+        self.glyphs.get(&c).unwrap_or(&self.fallback).clone()
+    }
+}
+
+/// Outline of a box filling the line, the usual "missing glyph" mark
+fn hollow_box(metrics: &LineMetrics) -> Glyph {
+    let height = metrics.ascent.max(2) as u16;
+    let width = (height / 2).max(2);
+    let coverage = (0..height)
+        .flat_map(|y| {
+            (0..width).map(move |x| {
+                let edge = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+                if edge {
+                    255
+                } else {
+                    0
+                }
+            })
+        })
+        .collect();
+
+    Glyph {
+        width,
+        height,
+        x_offset: 1,
+        y_offset: -(height as i32),
+        advance: (width + 2) as f32,
+        coverage,
+    }
+}
+
+fn scale_glyph(glyph: &Glyph, factor: u8) -> Glyph {
+    let factor_usize = factor as usize;
+    let width = glyph.width as usize * factor_usize;
+    let coverage = (0..glyph.height as usize * factor_usize)
+        .flat_map(|y| {
+            let row =
+                &glyph.coverage[y / factor_usize * glyph.width as usize..][..glyph.width as usize];
+            (0..width).map(move |x| row[x / factor_usize])
+        })
+        .collect();
+
+    Glyph {
+        width: glyph.width * factor as u16,
+        height: glyph.height * factor as u16,
+        x_offset: glyph.x_offset * factor as i32,
+        y_offset: glyph.y_offset * factor as i32,
+        advance: glyph.advance * factor as f32,
+        coverage,
+    }
+}
@@ -0,0 +1,63 @@
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+use lru::LruCache;
+
+use crate::tft_display::font::Glyph;
+
+/// Glyphs kept by default, enough for a few sizes of Latin text
+pub const DEFAULT_CAPACITY: usize = 512;
+
+/// Key of a cached glyph: font id, pixel size bits and character
+type Key = (usize, u32, char);
+
+/// Least recently used cache of rasterized glyphs, shared by fonts of any size
+pub struct GlyphCache {
+    glyphs: LruCache<Key, Arc<Glyph>>,
+}
+
+impl GlyphCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            glyphs: LruCache::new(NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN)),
+        }
+    }
+
+    /// New cache behind a mutex, ready to be shared between fonts
+    pub fn shared(capacity: usize) -> Arc<Mutex<Self>> {
+        Arc::new(Mutex::new(Self::new(capacity)))
+    }
+
+    /// Cached glyph of `c` in font `id` at `size`, rasterized with `rasterize` on a miss
+    pub fn get_or_insert(
+        &mut self,
+        id: usize,
+        size: f32,
+        c: char,
+        rasterize: impl FnOnce() -> Glyph,
+    ) -> Arc<Glyph> {
+        self.glyphs
+            .get_or_insert((id, size.to_bits(), c), || Arc::new(rasterize()))
+            .clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.glyphs.clear();
+    }
+}
+
+impl Default for GlyphCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
@@ -0,0 +1,50 @@
+pub mod bdf;
+pub mod bitmap;
+pub mod builtin;
+pub mod cache;
+pub mod pcf;
+pub mod text;
+pub mod truetype;
+
+use std::sync::Arc;
+
+/// Rasterized glyph, drawn through `Canvas::draw_coverage`
+///
+/// Offsets are relative to the pen position on the baseline, y growing downwards.
+#[derive(Clone, Debug, Default)]
+pub struct Glyph {
+    pub width: u16,
+    pub height: u16,
+    /// Left edge of the bitmap from the pen position
+    pub x_offset: i32,
+    /// Top edge of the bitmap from the baseline, usually negative
+    pub y_offset: i32,
+    /// Horizontal distance to the next pen position
+    pub advance: f32,
+    /// Row by row coverage, 0 transparent to 255 opaque
+    pub coverage: Vec<u8>,
+}
+
+/// Vertical metrics of a font at its size, in pixels
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LineMetrics {
+    /// Height above the baseline
+    pub ascent: i32,
+    /// Depth below the baseline, positive
+    pub descent: i32,
+    /// Distance from one baseline to the next
+    pub line_height: i32,
+}
+
+/// A font at a fixed pixel size
+pub trait Font {
+    fn line_metrics(&self) -> LineMetrics;
+
+    /// Glyph for `c`, the font's fallback glyph if it has none
+    fn glyph(&self, c: char) -> Arc<Glyph>;
+
+    /// Adjustment of the advance between `left` and `right`
+    fn kerning(&self, _left: char, _right: char) -> f32 {
+        0.0
+    }
+}
@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+
+use crate::tft_display::{
+    error::Error,
+    font::{bitmap::BitmapFont, Glyph, LineMetrics},
+    tft_display::Result,
+};
+
+const MAGIC: &[u8; 4] = b"\x01fcp";
+
+// Table types
+const ACCELERATORS: u32 = 1 << 1;
+const METRICS: u32 = 1 << 2;
+const BITMAPS: u32 = 1 << 3;
+const BDF_ENCODINGS: u32 = 1 << 5;
+const BDF_ACCELERATORS: u32 = 1 << 8;
+
+// Table format bits
+const COMPRESSED_METRICS: u32 = 0x100;
+const BYTE_MSB_FIRST: u32 = 1 << 2;
+const BIT_MSB_FIRST: u32 = 1 << 3;
+
+/// Marks unencoded characters in the encoding table
+const NO_GLYPH: u16 = 0xFFFF;
+
+/// Code points with the index of their glyph
+type Encodings = Vec<(u32, usize)>;
+
+pub fn is_pcf(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Parses a PCF (Portable Compiled Format) font, as produced by `bdftopcf`
+///
+/// Encodings are taken as Unicode code points like in `bdf::parse`.
+pub fn parse(bytes: &[u8]) -> Result<BitmapFont> {
+    if !is_pcf(bytes) {
+        return Err(decode_error("not a PCF file"));
+    }
+
+    let mut header = Reader::new(bytes, MAGIC.len(), false);
+    let count = header.u32()?;
+    let mut tables = HashMap::new();
+    for _ in 0..count {
+        let (kind, _format, _size, offset) =
+            (header.u32()?, header.u32()?, header.u32()?, header.u32()?);
+        tables.insert(kind, offset as usize);
+    }
+    let table = |kind| {
+        tables
+            .get(&kind)
+            .ok_or_else(|| decode_error(&format!("missing table {kind:#x}")))
+            .and_then(|&offset| Reader::table(bytes, offset))
+    };
+
+    let metrics = read_metrics(table(METRICS)?)?;
+    let bitmaps = read_bitmaps(table(BITMAPS)?, &metrics)?;
+    let (encodings, default_char) = read_encodings(table(BDF_ENCODINGS)?)?;
+
+    let (ascent, descent) = match table(BDF_ACCELERATORS).or_else(|_| table(ACCELERATORS)) {
+        Ok(accelerators) => read_accelerators(accelerators)?,
+        Err(_) => metrics.iter().fold((0, 0), |(ascent, descent), metric| {
+            (ascent.max(metric.ascent), descent.max(metric.descent))
+        }),
+    };
+
+    let mut glyphs = HashMap::new();
+    for (code, index) in encodings {
+        let (Some(c), Some(metric), Some(coverage)) =
+            (char::from_u32(code), metrics.get(index), bitmaps.get(index))
+        else {
+            continue;
+        };
+        glyphs.insert(
+            c,
+            Glyph {
+                width: metric.width(),
+                height: metric.height(),
+                x_offset: metric.left_bearing,
+                y_offset: -metric.ascent,
+                advance: metric.advance as f32,
+                coverage: coverage.clone(),
+            },
+        );
+    }
+
+    let line_height = ascent
+        .checked_add(descent)
+        .ok_or_else(|| decode_error("line height out of range"))?;
+    let metrics = LineMetrics {
+        ascent,
+        descent,
+        line_height,
+    };
+    Ok(BitmapFont::new(glyphs, metrics, default_char))
+}
+
+struct Metric {
+    left_bearing: i32,
+    right_bearing: i32,
+    advance: i32,
+    ascent: i32,
+    descent: i32,
+}
+
+impl Metric {
+    fn width(&self) -> u16 {
+        (self.right_bearing - self.left_bearing).max(0) as u16
+    }
+
+    fn height(&self) -> u16 {
+        (self.ascent + self.descent).max(0) as u16
+    }
+}
+
+fn read_metrics((mut reader, format): (Reader, u32)) -> Result<Vec<Metric>> {
+    if format & COMPRESSED_METRICS != 0 {
+        let count = reader.u16()?;
+        (0..count)
+            .map(|_| {
+                let mut next = || reader.u8().map(|value| value as i32 - 0x80);
+                Ok(Metric {
+                    left_bearing: next()?,
+                    right_bearing: next()?,
+                    advance: next()?,
+                    ascent: next()?,
+                    descent: next()?,
+                })
+            })
+            .collect()
+    } else {
+        let count = reader.u32()?;
+        (0..count)
+            .map(|_| {
+                let metric = Metric {
+                    left_bearing: reader.i16()? as i32,
+                    right_bearing: reader.i16()? as i32,
+                    advance: reader.i16()? as i32,
+                    ascent: reader.i16()? as i32,
+                    descent: reader.i16()? as i32,
+                };
+                // Attributes
+                reader.u16()?;
+                Ok(metric)
+            })
+            .collect()
+    }
+}
+
+/// Coverage of every glyph, in the order of the metrics table
+fn read_bitmaps((mut reader, format): (Reader, u32), metrics: &[Metric]) -> Result<Vec<Vec<u8>>> {
+    let count = reader.u32()? as usize;
+    let offsets = (0..count)
+        .map(|_| reader.u32().map(|offset| offset as usize))
+        .collect::<Result<Vec<_>>>()?;
+    let sizes = [reader.u32()?, reader.u32()?, reader.u32()?, reader.u32()?];
+
+    // Rows are padded to 1, 2, 4 or 8 bytes and stored in scan units of 1, 2 or 4 bytes
+    let pad = 1 << (format & 3);
+    let scan_unit = 1 << ((format >> 4) & 3);
+    let data = reader.take(sizes[(format & 3) as usize] as usize)?;
+
+    let swap_bytes = (format & BYTE_MSB_FIRST != 0) != (format & BIT_MSB_FIRST != 0);
+    let lsb_first = format & BIT_MSB_FIRST == 0;
+
+    offsets
+        .iter()
+        .zip(metrics)
+        .map(|(&offset, metric)| {
+            let (width, height) = (metric.width() as usize, metric.height() as usize);
+            let stride = width.div_ceil(8).div_ceil(pad) * pad;
+            let end = stride
+                .checked_mul(height)
+                .and_then(|size| size.checked_add(offset))
+                .ok_or_else(|| decode_error("bitmap out of range"))?;
+            let mut bitmap = data
+                .get(offset..end)
+                .ok_or_else(|| decode_error("bitmap out of range"))?
+                .to_vec();
+
+            if swap_bytes && scan_unit > 1 {
+                bitmap
+                    .chunks_exact_mut(scan_unit)
+                    .for_each(|unit| unit.reverse());
+            }
+            if lsb_first {
+                bitmap
+                    .iter_mut()
+                    .for_each(|byte| *byte = byte.reverse_bits());
+            }
+
+            Ok((0..height)
+                .flat_map(|y| {
+                    let row = &bitmap[y * stride..];
+                    (0..width).map(move |x| ((row[x / 8] >> (7 - x % 8)) & 1) * 255)
+                })
+                .collect())
+        })
+        .collect()
+}
+
+/// Encoded characters and the default character
+fn read_encodings((mut reader, _): (Reader, u32)) -> Result<(Encodings, Option<char>)> {
+    let (min_byte2, max_byte2) = (reader.u16()? as u32, reader.u16()? as u32);
+    let (min_byte1, max_byte1) = (reader.u16()? as u32, reader.u16()? as u32);
+    let default_char = char::from_u32(reader.u16()? as u32);
+
+    let mut encodings = Vec::new();
+    for byte1 in min_byte1..=max_byte1 {
+        for byte2 in min_byte2..=max_byte2 {
+            let index = reader.u16()?;
+            if index != NO_GLYPH {
+                encodings.push((byte1 << 8 | byte2, index as usize));
+            }
+        }
+    }
+    Ok((encodings, default_char))
+}
+
+/// Font ascent and descent
+fn read_accelerators((mut reader, _): (Reader, u32)) -> Result<(i32, i32)> {
+    // Flags: no overlap, constant metrics, terminal font, constant width, ink inside,
+    // ink metrics, draw direction and padding
+    reader.take(8)?;
+    Ok((reader.i32()?, reader.i32()?))
+}
+
+/// Cursor over a table, in the byte order given by its format
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8], position: usize, big_endian: bool) -> Self {
+        Self {
+            bytes,
+            position,
+            big_endian,
+        }
+    }
+
+    /// Reader past the format field at the start of a table, with the format
+    fn table(bytes: &'a [u8], offset: usize) -> Result<(Self, u32)> {
+        // The format itself is always little endian
+        let mut reader = Self::new(bytes, offset, false);
+        let format = reader.u32()?;
+        reader.big_endian = format & BYTE_MSB_FIRST != 0;
+        Ok((reader, format))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .position
+            .checked_add(len)
+            .ok_or_else(|| decode_error("unexpected end of file"))?;
+        let bytes = self
+            .bytes
+            .get(self.position..end)
+            .ok_or_else(|| decode_error("unexpected end of file"))?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array: [u8; N] = self.take(N)?.try_into().expect("length checked by take");
+        if self.big_endian {
+            array.reverse();
+        }
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        self.array().map(u16::from_le_bytes)
+    }
+
+    fn i16(&mut self) -> Result<i16> {
+        self.array().map(i16::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn i32(&mut self) -> Result<i32> {
+        self.array().map(i32::from_le_bytes)
+    }
+}
+
+fn decode_error(message: &str) -> Error {
+    Error::Decode(format!("PCF: {message}"))
+}
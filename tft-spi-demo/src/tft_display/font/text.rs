@@ -0,0 +1,107 @@
+use crate::tft_display::{
+    enums::Align,
+    font::{Font, Glyph},
+    tft_display::Result,
+};
+
+/// Calls `place` for every glyph of a single line with its pen position, kerning applied
+pub fn layout_line(
+    font: &dyn Font,
+    line: &str,
+    x: f32,
+    mut place: impl FnMut(f32, &Glyph) -> Result<()>,
+) -> Result<()> {
+    let mut pen = x;
+    let mut previous = None;
+    for c in line.chars() {
+        if let Some(previous) = previous {
+            pen += font.kerning(previous, c);
+        }
+        let glyph = font.glyph(c);
+        place(pen, &glyph)?;
+        pen += glyph.advance;
+        previous = Some(c);
+    }
+    Ok(())
+}
+
+/// Advance width of a single line in pixels
+pub fn line_width(font: &dyn Font, line: &str) -> f32 {
+    let mut width = 0.0;
+    let mut previous = None;
+    for c in line.chars() {
+        if let Some(previous) = previous {
+            width += font.kerning(previous, c);
+        }
+        width += font.glyph(c).advance;
+        previous = Some(c);
+    }
+    width
+}
+
+/// Size of `text` in pixels: its widest line and the height of all lines
+pub fn measure(font: &dyn Font, text: &str) -> (u16, u16) {
+    let line_height = font.line_metrics().line_height;
+    let (lines, width) = text.lines().fold((0, 0f32), |(lines, width), line| {
+        (lines + 1, width.max(line_width(font, line)))
+    });
+    (width.ceil() as u16, (lines * line_height).max(0) as u16)
+}
+
+/// Left edge of a line `width` wide, aligned within `left`..`left + box_width`
+pub fn align_x(align: Align, left: i32, box_width: u16, width: f32) -> f32 {
+    let slack = box_width as f32 - width;
+    match align {
+        Align::Left => left as f32,
+        Align::Center => left as f32 + (slack / 2.0).round(),
+        Align::Right => left as f32 + slack,
+    }
+}
+
+/// Splits `text` into lines no wider than `max_width`
+///
+/// Lines break at spaces, words longer than a line between characters. Line breaks
+/// in `text` are kept.
+pub fn wrap<'a>(font: &dyn Font, text: &'a str, max_width: u16) -> Vec<&'a str> {
+    let max_width = max_width as f32;
+    let mut lines = Vec::new();
+
+    for paragraph in text.lines() {
+        let mut start = 0;
+        let mut width = 0.0;
+        let mut previous = None;
+        let mut last_space = None;
+
+        for (i, c) in paragraph.char_indices() {
+            let advance = |previous: Option<char>| {
+                previous.map_or(0.0, |previous| font.kerning(previous, c)) + font.glyph(c).advance
+            };
+            let mut step = advance(previous);
+
+            if c != ' ' && i > start && width + step > max_width {
+                match last_space.filter(|&space| space > start) {
+                    Some(space) => {
+                        lines.push(paragraph[start..space].trim_end());
+                        start = space + 1;
+                    }
+                    None => {
+                        lines.push(&paragraph[start..i]);
+                        start = i;
+                    }
+                }
+                last_space = None;
+                width = line_width(font, &paragraph[start..i]);
+                previous = paragraph[start..i].chars().last();
+                step = advance(previous);
+            }
+
+            if c == ' ' {
+                last_space = Some(i);
+            }
+            width += step;
+            previous = Some(c);
+        }
+        lines.push(paragraph[start..].trim_end());
+    }
+    lines
+}
@@ -0,0 +1,132 @@
+use std::{
+    fs,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use fontdue::FontSettings;
+
+use crate::{
+    tft_display::{
+        error::Error,
+        font::{
+            cache::{GlyphCache, DEFAULT_CAPACITY},
+            Font, Glyph, LineMetrics,
+        },
+        tft_display::Result,
+    },
+    util::MutexExt,
+};
+
+/// Tells faces apart in the shared glyph cache
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Loaded TTF/OTF font, rasterized at any size with `sized`
+///
+/// Cheap to clone, clones share the parsed font and the glyph cache.
+#[derive(Clone)]
+pub struct FontFace {
+    id: usize,
+    font: Arc<fontdue::Font>,
+    cache: Arc<Mutex<GlyphCache>>,
+}
+
+impl FontFace {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    /// Parses a TTF/OTF file, with its own glyph cache
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::with_cache(bytes, GlyphCache::shared(DEFAULT_CAPACITY))
+    }
+
+    /// Parses a TTF/OTF file, caching its glyphs in `cache`
+    pub fn with_cache(bytes: &[u8], cache: Arc<Mutex<GlyphCache>>) -> Result<Self> {
+        let font = fontdue::Font::from_bytes(bytes, FontSettings::default())
+            .map_err(|msg| Error::Decode(msg.to_string()))?;
+        Ok(Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            font: Arc::new(font),
+            cache,
+        })
+    }
+
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.font.has_glyph(c)
+    }
+
+    /// The face at `size` pixels per em
+    pub fn sized(&self, size: f32) -> TrueTypeFont {
+        TrueTypeFont {
+            face: self.clone(),
+            size,
+        }
+    }
+}
+
+/// A `FontFace` at a pixel size
+#[derive(Clone)]
+pub struct TrueTypeFont {
+    face: FontFace,
+    size: f32,
+}
+
+impl TrueTypeFont {
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    pub fn face(&self) -> &FontFace {
+        &self.face
+    }
+}
+
+impl Font for TrueTypeFont {
+    fn line_metrics(&self) -> LineMetrics {
+        let Some(metrics) = self.face.font.horizontal_line_metrics(self.size) else {
+            // No horizontal metrics table, estimate from the size
+            let ascent = (self.size * 0.8).ceil() as i32;
+            let descent = (self.size * 0.2).ceil() as i32;
+            return LineMetrics {
+                ascent,
+                descent,
+                line_height: ascent + descent,
+            };
+        };
+        LineMetrics {
+            ascent: metrics.ascent.ceil() as i32,
+            descent: (-metrics.descent).ceil() as i32,
+            line_height: metrics.new_line_size.round() as i32,
+        }
+    }
+
+    fn glyph(&self, c: char) -> Arc<Glyph> {
+        let font = &self.face.font;
+        self.face
+            .cache
+            .xlock()
+            .get_or_insert(self.face.id, self.size, c, || {
+                // Characters without a glyph rasterize the font's .notdef glyph
+                let (metrics, coverage) = font.rasterize(c, self.size);
+                Glyph {
+                    width: metrics.width as u16,
+                    height: metrics.height as u16,
+                    x_offset: metrics.xmin,
+                    y_offset: -(metrics.ymin + metrics.height as i32),
+                    advance: metrics.advance_width,
+                    coverage,
+                }
+            })
+    }
+
+    fn kerning(&self, left: char, right: char) -> f32 {
+        self.face
+            .font
+            .horizontal_kern(left, right, self.size)
+            .unwrap_or(0.0)
+    }
+}
@@ -0,0 +1,235 @@
+use crate::tft_display::{
+    canvas::Canvas, color::Color, dither::Dither, error::Error, geometry::Rect,
+    tft_display::Result,
+};
+
+/// Off-screen pixel buffer, drawn into like the display and sent with `TftDisplay::flush`
+///
+/// Unlike the display its pixels can be read back, which blending and anti-aliasing need.
+pub struct Framebuffer {
+    width: u16,
+    height: u16,
+    pixels: Vec<Color>,
+    clips: Vec<Rect>,
+    dither: Dither,
+}
+
+impl Framebuffer {
+    pub fn new(width: u16, height: u16, color: Color) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![color; width as usize * height as usize],
+            clips: Vec::new(),
+            dither: Dither::None,
+        }
+    }
+
+    /// Wraps existing pixels, row by row
+    pub fn from_pixels(width: u16, height: u16, pixels: Vec<Color>) -> Result<Self> {
+        let expected = width as usize * height as usize;
+        if pixels.len() != expected {
+            return Err(Error::Buffer {
+                given: pixels.len(),
+                expected,
+            });
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels,
+            clips: Vec::new(),
+            dither: Dither::None,
+        })
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    pub fn set_dither(&mut self, dither: Dither) {
+        self.dither = dither;
+    }
+
+    /// Pixels of row `y` from column `x`, `w` long, all inside the buffer
+    pub fn row(&self, x: u16, y: u16, w: u16) -> &[Color] {
+        let start = y as usize * self.width as usize + x as usize;
+        &self.pixels[start..start + w as usize]
+    }
+
+    /// Sets a pixel ignoring the clip, positions outside the buffer are ignored
+    pub fn set_pixel(&mut self, x: i32, y: i32, color: Color) {
+        if let Some(index) = self.index(x, y) {
+            self.pixels[index] = color;
+        }
+    }
+
+    /// Copies `rect` of `source` to (`x`, `y`), clipped
+    pub fn copy_from(&mut self, source: &Framebuffer, rect: Rect, x: i32, y: i32) {
+        let Some(rect) = rect.intersect(source.bounds()) else {
+            return;
+        };
+        let target = Rect::new(x, y, rect.w, rect.h);
+        let Some(visible) = target.intersect(self.clip()) else {
+            return;
+        };
+
+        let (skip_x, skip_y) = (visible.x - x, visible.y - y);
+        for row in 0..visible.h as i32 {
+            let src = source.row(
+                (rect.x + skip_x) as u16,
+                (rect.y + skip_y + row) as u16,
+                visible.w,
+            );
+            let start = (visible.y + row) as usize * self.width as usize + visible.x as usize;
+            self.pixels[start..start + visible.w as usize].copy_from_slice(src);
+        }
+    }
+
+    /// Moves the rows of `rect` up by `dy` (down if negative), like the display's
+    /// hardware scrolling does with a scroll area
+    ///
+    /// The rows moved in keep their old pixels, draw over them.
+    pub fn scroll(&mut self, rect: Rect, dy: i32) {
+        let Some(rect) = rect.intersect(self.bounds()) else {
+            return;
+        };
+        if dy == 0 || dy.unsigned_abs() >= rect.h as u32 {
+            return;
+        }
+
+        let (width, w) = (self.width as usize, rect.w as usize);
+        let mut copy_row = |from: i32, to: i32| {
+            let start = from as usize * width + rect.x as usize;
+            let target = to as usize * width + rect.x as usize;
+            self.pixels.copy_within(start..start + w, target);
+        };
+
+        // Copy in the direction that doesn't overwrite rows still to be moved
+        let moved = rect.h as i32 - dy.abs();
+        if dy > 0 {
+            for y in rect.y..rect.y + moved {
+                copy_row(y + dy, y);
+            }
+        } else {
+            for y in (rect.y..rect.y + moved).rev() {
+                copy_row(y, y - dy);
+            }
+        }
+    }
+
+    /// Moves the pixels in `rect` left by `dx` columns (right if negative)
+    ///
+    /// Like `scroll`, the columns moved in keep their old pixels.
+    pub fn shift(&mut self, rect: Rect, dx: i32) {
+        let Some(rect) = rect.intersect(self.bounds()) else {
+            return;
+        };
+        if dx == 0 || dx.unsigned_abs() >= rect.w as u32 {
+            return;
+        }
+
+        let moved = (rect.w as i32 - dx.abs()) as usize;
+        let (from, to) = if dx > 0 {
+            (rect.x + dx, rect.x)
+        } else {
+            (rect.x, rect.x - dx)
+        };
+        for y in rect.y..rect.bottom() {
+            let row = y as usize * self.width as usize;
+            let start = row + from as usize;
+            self.pixels.copy_within(start..start + moved, row + to as usize);
+        }
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        Some(y as usize * self.width as usize + x as usize)
+    }
+}
+
+impl Canvas for Framebuffer {
+    fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    fn clip(&self) -> Rect {
+        let bounds = self.bounds();
+        match self.clips.last() {
+            Some(clip) => clip.intersect(bounds).unwrap_or(Rect::new(clip.x, clip.y, 0, 0)),
+            None => bounds,
+        }
+    }
+
+    fn push_clip(&mut self, rect: Rect) {
+        let clip = self
+            .clip()
+            .intersect(rect)
+            .unwrap_or(Rect::new(rect.x, rect.y, 0, 0));
+        self.clips.push(clip);
+    }
+
+    fn pop_clip(&mut self) -> Option<Rect> {
+        self.clips.pop()
+    }
+
+    fn dither(&self) -> Dither {
+        self.dither
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: Color) -> Result<()> {
+        let Some(rect) = rect.intersect(self.clip()) else {
+            return Ok(());
+        };
+        for y in rect.y..rect.bottom() {
+            let start = y as usize * self.width as usize + rect.x as usize;
+            self.pixels[start..start + rect.w as usize].fill(color);
+        }
+        Ok(())
+    }
+
+    fn draw_rows(&mut self, rect: Rect, row: &mut dyn FnMut(u16, &mut Vec<Color>)) -> Result<()> {
+        let Some(visible) = rect.intersect(self.clip()) else {
+            return Ok(());
+        };
+        let skip_x = (visible.x - rect.x) as usize;
+        let skip_y = (visible.y - rect.y) as u16;
+
+        let mut colors = Vec::with_capacity(rect.w as usize);
+        for index in 0..visible.h {
+            colors.clear();
+            row(skip_y + index, &mut colors);
+
+            let start = (visible.y as usize + index as usize) * self.width as usize + visible.x as usize;
+            let source = colors.iter().skip(skip_x).take(visible.w as usize);
+            for (pixel, &color) in self.pixels[start..start + visible.w as usize].iter_mut().zip(source) {
+                *pixel = color;
+            }
+        }
+        Ok(())
+    }
+
+    fn pixel(&self, x: i32, y: i32) -> Option<Color> {
+        self.index(x, y).map(|index| self.pixels[index])
+    }
+
+    fn blend_pixel(&mut self, x: i32, y: i32, color: Color, alpha: u8) -> Result<()> {
+        if alpha == 0 || !self.clip().contains(x, y) {
+            return Ok(());
+        }
+        if let Some(index) = self.index(x, y) {
+            self.pixels[index] = self.pixels[index].blend(color, alpha);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use crate::tft_display::error::Error;
+
+/// Number of parameters of the Positive/Negative Gamma Control commands (0xE0/0xE1)
+pub const GAMMA_TABLE_LEN: usize = 15;
+
+/// Valid bits of each gamma parameter (VP63/VN63 .. VP0/VN0)
+///
+/// Parameter 8 packs two 4-bit voltages, all others hold a single 4 to 7-bit value.
+const GAMMA_MASKS: [u8; GAMMA_TABLE_LEN] = [
+    0x0F, 0x3F, 0x3F, 0x0F, 0x1F, 0x0F, 0x7F, 0xFF,
+    0x7F, 0x0F, 0x1F, 0x0F, 0x3F, 0x3F, 0x0F,
+];
+
+/// Positive and negative gamma correction tables
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GammaCurve {
+    pub positive: [u8; GAMMA_TABLE_LEN],
+    pub negative: [u8; GAMMA_TABLE_LEN],
+}
+
+impl GammaCurve {
+    /// Curve written by `TftDisplay::initialize`
+    pub const DEFAULT: GammaCurve = GammaCurve {
+        positive: [
+            0x0F, 0x1F, 0x1C, 0x0C, 0x0F, 0x08, 0x48, 0x98,
+            0x37, 0x0A, 0x13, 0x04, 0x11, 0x0D, 0x00,
+        ],
+        negative: [
+            0x0F, 0x32, 0x2E, 0x0B, 0x0D, 0x05, 0x47, 0x75,
+            0x37, 0x06, 0x10, 0x03, 0x24, 0x20, 0x00,
+        ],
+    };
+
+    /// Curve from the LCDWIKI 3.5" ILI9488 init sequence, darker mid-tones
+    pub const LCDWIKI: GammaCurve = GammaCurve {
+        positive: [
+            0x00, 0x03, 0x09, 0x08, 0x16, 0x0A, 0x3F, 0x78,
+            0x4C, 0x09, 0x0A, 0x08, 0x16, 0x1A, 0x0F,
+        ],
+        negative: [
+            0x00, 0x16, 0x19, 0x03, 0x0F, 0x05, 0x32, 0x45,
+            0x46, 0x04, 0x0E, 0x0D, 0x35, 0x37, 0x0F,
+        ],
+    };
+
+    /// Same table for both polarities, e.g. when tuning a single unit
+    pub const fn symmetric(table: [u8; GAMMA_TABLE_LEN]) -> Self {
+        Self {
+            positive: table,
+            negative: table,
+        }
+    }
+
+    /// Checks every parameter against the bits the controller accepts
+    ///
+    /// # Errors
+    ///
+    /// Errors with the first out of range parameter, counting the negative table
+    /// after the positive one
+    pub fn validate(&self) -> Result<(), Error> {
+        let params = self.positive.iter().chain(self.negative.iter());
+        let masks = GAMMA_MASKS.iter().cycle();
+
+        for (index, (&value, &max)) in params.zip(masks).enumerate() {
+            if value & !max != 0 {
+                return Err(Error::Gamma { index, value, max });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for GammaCurve {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
@@ -0,0 +1,104 @@
+/// Position in screen coordinates
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    /// Squared distance to `other`, enough to compare distances
+    pub fn distance_squared(&self, other: Point) -> i32 {
+        (self.x - other.x).pow(2) + (self.y - other.y).pow(2)
+    }
+}
+
+/// Axis aligned rectangle in screen coordinates
+///
+/// The position may be negative or beyond the screen, drawing clips it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub w: u16,
+    pub h: u16,
+}
+
+impl Rect {
+    pub const fn new(x: i32, y: i32, w: u16, h: u16) -> Self {
+        Self { x, y, w, h }
+    }
+
+    /// Rectangle at the origin, e.g. the whole screen
+    pub const fn from_size(w: u16, h: u16) -> Self {
+        Self { x: 0, y: 0, w, h }
+    }
+
+    /// One past the last column
+    pub const fn right(&self) -> i32 {
+        self.x + self.w as i32
+    }
+
+    /// One past the last row
+    pub const fn bottom(&self) -> i32 {
+        self.y + self.h as i32
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.w == 0 || self.h == 0
+    }
+
+    pub fn center(&self) -> (i32, i32) {
+        (self.x + self.w as i32 / 2, self.y + self.h as i32 / 2)
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    pub fn contains_point(&self, point: Point) -> bool {
+        self.contains(point.x, point.y)
+    }
+
+    /// Overlapping part of both rectangles, `None` if they don't overlap
+    pub fn intersect(&self, other: Rect) -> Option<Rect> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+
+        if right <= x || bottom <= y {
+            return None;
+        }
+        Some(Rect::new(x, y, (right - x) as u16, (bottom - y) as u16))
+    }
+
+    /// Smallest rectangle covering both, empty rectangles are ignored
+    pub fn union(&self, other: Rect) -> Rect {
+        if self.is_empty() {
+            return other;
+        } else if other.is_empty() {
+            return *self;
+        }
+
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+        Rect::new(x, y, (right - x) as u16, (bottom - y) as u16)
+    }
+
+    pub fn translate(&self, dx: i32, dy: i32) -> Rect {
+        Rect::new(self.x + dx, self.y + dy, self.w, self.h)
+    }
+
+    /// Shrinks every side by `amount`, never below zero size
+    pub fn inset(&self, amount: u16) -> Rect {
+        let w = self.w.saturating_sub(amount.saturating_mul(2));
+        let h = self.h.saturating_sub(amount.saturating_mul(2));
+        Rect::new(self.x + amount as i32, self.y + amount as i32, w, h)
+    }
+}
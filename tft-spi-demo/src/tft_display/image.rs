@@ -0,0 +1,271 @@
+use std::{fs, path::Path};
+
+use png::{ColorType, Transformations};
+
+use crate::tft_display::{color::Color, error::Error};
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Resampling filter used when scaling images
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Filter {
+    Nearest,
+    Bilinear,
+}
+
+/// Decoded image with 8-bit RGB pixels, alpha already composited
+pub struct Image {
+    width: u16,
+    height: u16,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    pub fn new(width: u16, height: u16, pixels: Vec<[u8; 3]>) -> Result<Self> {
+        let expected = width as usize * height as usize;
+        if pixels.len() != expected {
+            return Err(Error::Buffer {
+                given: pixels.len(),
+                expected,
+            });
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Reads and decodes a BMP, PNG or QOI file
+    pub fn open(path: impl AsRef<Path>, background: Color) -> Result<Self> {
+        Self::decode(&fs::read(path)?, background)
+    }
+
+    /// Decodes a BMP, PNG or QOI image, detected by its signature
+    ///
+    /// Transparent pixels are composited against `background`.
+    pub fn decode(bytes: &[u8], background: Color) -> Result<Self> {
+        let background = background.to_rgb888();
+
+        if bytes.starts_with(b"BM") {
+            Self::decode_bmp(bytes)
+        } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Self::decode_png(bytes, background)
+        } else if bytes.starts_with(b"qoif") {
+            Self::decode_qoi(bytes, background)
+        } else {
+            Err(Error::Decode("unknown image format".to_string()))
+        }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[[u8; 3]] {
+        &self.pixels
+    }
+
+    /// Scales the image to exactly `width` x `height`
+    pub fn resize(&self, width: u16, height: u16, filter: Filter) -> Image {
+        let mut pixels = Vec::with_capacity(width as usize * height as usize);
+        let scale_x = self.width as f32 / width.max(1) as f32;
+        let scale_y = self.height as f32 / height.max(1) as f32;
+
+        for y in 0..height {
+            for x in 0..width {
+                // Sample at the pixel center
+                let src_x = (x as f32 + 0.5) * scale_x - 0.5;
+                let src_y = (y as f32 + 0.5) * scale_y - 0.5;
+                pixels.push(match filter {
+                    Filter::Nearest => self.pixel(src_x.round() as i32, src_y.round() as i32),
+                    Filter::Bilinear => self.bilinear(src_x, src_y),
+                });
+            }
+        }
+
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Scales the image to the largest size fitting `width` x `height`, keeping its
+    /// aspect ratio
+    pub fn fit(&self, width: u16, height: u16, filter: Filter) -> Image {
+        let scale = (width as f32 / self.width.max(1) as f32)
+            .min(height as f32 / self.height.max(1) as f32);
+        let fit_width = ((self.width as f32 * scale).round() as u16).clamp(1, width.max(1));
+        let fit_height = ((self.height as f32 * scale).round() as u16).clamp(1, height.max(1));
+
+        self.resize(fit_width, fit_height, filter)
+    }
+
+    /// Pixel with coordinates clamped to the image
+    fn pixel(&self, x: i32, y: i32) -> [u8; 3] {
+        let x = x.clamp(0, self.width as i32 - 1) as usize;
+        let y = y.clamp(0, self.height as i32 - 1) as usize;
+        self.pixels[y * self.width as usize + x]
+    }
+
+    fn bilinear(&self, x: f32, y: f32) -> [u8; 3] {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i32, y0 as i32);
+
+        let top_left = self.pixel(x0, y0);
+        let top_right = self.pixel(x0 + 1, y0);
+        let bottom_left = self.pixel(x0, y0 + 1);
+        let bottom_right = self.pixel(x0 + 1, y0 + 1);
+
+        let mut out = [0; 3];
+        for (i, channel) in out.iter_mut().enumerate() {
+            let top = top_left[i] as f32 * (1.0 - fx) + top_right[i] as f32 * fx;
+            let bottom = bottom_left[i] as f32 * (1.0 - fx) + bottom_right[i] as f32 * fx;
+            *channel = (top * (1.0 - fy) + bottom * fy).round() as u8;
+        }
+        out
+    }
+
+    /// Uncompressed 24-bit (and 32-bit) Windows bitmaps, bottom-up or top-down
+    fn decode_bmp(bytes: &[u8]) -> Result<Self> {
+        let u16_at = |offset: usize| {
+            bytes
+                .get(offset..offset + 2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        };
+        let u32_at = |offset: usize| {
+            bytes
+                .get(offset..offset + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        };
+        let truncated = || Error::Decode("truncated BMP header".to_string());
+
+        let data_offset = u32_at(10).ok_or_else(truncated)? as usize;
+        let width = u32_at(18).ok_or_else(truncated)? as i32;
+        let height = u32_at(22).ok_or_else(truncated)? as i32;
+        let bits_per_pixel = u16_at(28).ok_or_else(truncated)?;
+        let compression = u32_at(30).ok_or_else(truncated)?;
+
+        // BI_RGB, or BI_BITFIELDS for 32 bits with 8-bit channel masks
+        if !(bits_per_pixel == 24 && compression == 0
+            || bits_per_pixel == 32 && (compression == 0 || compression == 3))
+        {
+            return Err(Error::Decode(format!(
+                "unsupported BMP: {bits_per_pixel} bits per pixel, compression {compression}"
+            )));
+        }
+        let masks = match compression {
+            3 => [54, 58, 62].map(u32_at),
+            _ => [Some(0x00FF_0000), Some(0x0000_FF00), Some(0x0000_00FF)],
+        };
+        let [red, green, blue] = masks.map(|mask| mask.and_then(channel_shift));
+        let (Some(red), Some(green), Some(blue)) = (red, green, blue) else {
+            return Err(Error::Decode(format!("unsupported BMP channel masks: {masks:x?}")));
+        };
+
+        let top_down = height < 0;
+        let width = Self::dimension(width.unsigned_abs())?;
+        let height = Self::dimension(height.unsigned_abs())?;
+        let bytes_per_pixel = bits_per_pixel as usize / 8;
+        let stride = (width as usize * bytes_per_pixel).div_ceil(4) * 4;
+
+        let truncated = || Error::Decode("truncated BMP pixel data".to_string());
+        let end = stride
+            .checked_mul(height as usize)
+            .and_then(|size| size.checked_add(data_offset))
+            .ok_or_else(truncated)?;
+        let data = bytes.get(data_offset..end).ok_or_else(truncated)?;
+
+        let mut pixels = Vec::with_capacity(width as usize * height as usize);
+        for y in 0..height as usize {
+            let row = if top_down { y } else { height as usize - 1 - y };
+            let row = &data[row * stride..][..width as usize * bytes_per_pixel];
+            pixels.extend(row.chunks_exact(bytes_per_pixel).map(|pixel| {
+                let mut le = [0; 4];
+                le[..bytes_per_pixel].copy_from_slice(pixel);
+                let pixel = u32::from_le_bytes(le);
+                [red, green, blue].map(|shift| (pixel >> shift) as u8)
+            }));
+        }
+
+        Self::new(width, height, pixels)
+    }
+
+    /// Any PNG, palette and low bit depths are expanded and 16-bit channels stripped
+    fn decode_png(bytes: &[u8], background: [u8; 3]) -> Result<Self> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(Transformations::normalize_to_color8());
+
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+
+        let width = Self::dimension(info.width)?;
+        let height = Self::dimension(info.height)?;
+        let data = &buffer[..info.buffer_size()];
+
+        let pixels = match info.color_type {
+            ColorType::Rgb => data.chunks_exact(3).map(|p| [p[0], p[1], p[2]]).collect(),
+            ColorType::Rgba => data
+                .chunks_exact(4)
+                .map(|p| composite([p[0], p[1], p[2]], p[3], background))
+                .collect(),
+            ColorType::Grayscale => data.iter().map(|&l| [l, l, l]).collect(),
+            ColorType::GrayscaleAlpha => data
+                .chunks_exact(2)
+                .map(|p| composite([p[0], p[0], p[0]], p[1], background))
+                .collect(),
+            ColorType::Indexed => {
+                return Err(Error::Decode("PNG palette was not expanded".to_string()))
+            }
+        };
+
+        Self::new(width, height, pixels)
+    }
+
+    fn decode_qoi(bytes: &[u8], background: [u8; 3]) -> Result<Self> {
+        let (header, data) = qoi::decode_to_vec(bytes)?;
+
+        let width = Self::dimension(header.width)?;
+        let height = Self::dimension(header.height)?;
+
+        let pixels = match header.channels {
+            qoi::Channels::Rgb => data.chunks_exact(3).map(|p| [p[0], p[1], p[2]]).collect(),
+            qoi::Channels::Rgba => data
+                .chunks_exact(4)
+                .map(|p| composite([p[0], p[1], p[2]], p[3], background))
+                .collect(),
+        };
+
+        Self::new(width, height, pixels)
+    }
+
+    fn dimension(size: u32) -> Result<u16> {
+        u16::try_from(size)
+            .ok()
+            .filter(|&size| size > 0)
+            .ok_or_else(|| Error::Decode(format!("unsupported image dimension: {size}")))
+    }
+}
+
+/// Shift of an 8-bit wide BMP channel mask, `None` for other masks
+fn channel_shift(mask: u32) -> Option<u32> {
+    let shift = mask.trailing_zeros();
+    (mask != 0 && mask >> shift == 0xFF).then_some(shift)
+}
+
+/// Blends `rgb` with coverage `alpha` over `background`
+fn composite(rgb: [u8; 3], alpha: u8, background: [u8; 3]) -> [u8; 3] {
+    let alpha = alpha as u16;
+    let mut out = [0; 3];
+    for (i, channel) in out.iter_mut().enumerate() {
+        *channel = ((rgb[i] as u16 * alpha + background[i] as u16 * (255 - alpha) + 127) / 255) as u8;
+    }
+    out
+}
@@ -1,4 +1,23 @@
+pub mod antialias;
+pub mod canvas;
 pub mod color;
+pub mod dirty;
+pub mod dither;
+pub mod double_buffer;
 pub mod enums;
 pub mod error;
+pub mod fill;
+pub mod font;
+pub mod framebuffer;
+pub mod gamma;
+pub mod geometry;
+pub mod image;
+pub mod orientation;
+pub mod palette;
+pub mod region;
+pub mod scene;
+pub mod sprite;
+#[allow(clippy::module_inception)]
 pub mod tft_display;
+pub mod tilemap;
+pub mod vsync;
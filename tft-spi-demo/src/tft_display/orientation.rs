@@ -0,0 +1,112 @@
+use crate::tft_display::enums::{ColorOrder, MadControl, PcbType, Rotate};
+
+/// Display orientation, i.e. everything that ends up in the MADCTL register
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Orientation {
+    pub rotation: Rotate,
+    /// Mirrors the picture left to right (after rotating)
+    pub mirror_x: bool,
+    /// Mirrors the picture top to bottom (after rotating)
+    pub mirror_y: bool,
+    /// `None` uses the order the PCB is wired for, see `PcbType::color_order`
+    pub color_order: Option<ColorOrder>,
+    /// Refreshes the panel from the bottom row up (ML)
+    pub refresh_bottom_to_top: bool,
+    /// Refreshes the panel from the right column to the left (MH)
+    pub refresh_right_to_left: bool,
+}
+
+impl Orientation {
+    pub const fn new(rotation: Rotate) -> Self {
+        Self {
+            rotation,
+            mirror_x: false,
+            mirror_y: false,
+            color_order: None,
+            refresh_bottom_to_top: false,
+            refresh_right_to_left: false,
+        }
+    }
+
+    pub const fn mirrored(mut self, mirror_x: bool, mirror_y: bool) -> Self {
+        self.mirror_x = mirror_x;
+        self.mirror_y = mirror_y;
+        self
+    }
+
+    pub const fn with_color_order(mut self, color_order: ColorOrder) -> Self {
+        self.color_order = Some(color_order);
+        self
+    }
+
+    /// Panel refresh direction, e.g. to move tearing away from where content changes
+    ///
+    /// Only the order the panel is scanned in changes, not the picture.
+    pub const fn with_refresh_order(mut self, bottom_to_top: bool, right_to_left: bool) -> Self {
+        self.refresh_bottom_to_top = bottom_to_top;
+        self.refresh_right_to_left = right_to_left;
+        self
+    }
+
+    /// Whether rows and columns are exchanged, i.e. width and height swap
+    pub fn is_landscape(&self) -> bool {
+        matches!(self.rotation, Rotate::Degrees90 | Rotate::Degrees270)
+    }
+
+    /// Computes the Memory Access Control (0x36) value
+    ///
+    /// The black PCB mounts the panel upside down compared to the red/green ones. Mirroring
+    /// is applied to the rotated picture, so with rows and columns exchanged (MV) the
+    /// horizontal flip is done by the row order bit (MY) and the vertical one by MX.
+    pub fn madctl(&self, pcb_type: &PcbType) -> u8 {
+        use MadControl::*;
+
+        let rotation: &[MadControl] = match (self.rotation, pcb_type) {
+            (Rotate::Degrees0, PcbType::Black) => &[MadctlMx, MadctlMy],
+            (Rotate::Degrees90, PcbType::Black) => &[MadctlMv, MadctlMy],
+            (Rotate::Degrees180, PcbType::Black) => &[],
+            (Rotate::Degrees270, PcbType::Black) => &[MadctlMv, MadctlMx],
+            (Rotate::Degrees0, _) => &[MadctlMy],
+            (Rotate::Degrees90, _) => &[MadctlMv],
+            (Rotate::Degrees180, _) => &[MadctlMx],
+            (Rotate::Degrees270, _) => &[MadctlMv, MadctlMx, MadctlMy],
+        };
+        let mut madctl = rotation.iter().fold(0, |bits, &flag| bits | flag as u8);
+
+        let (flip_x, flip_y) = if self.is_landscape() {
+            (MadctlMy, MadctlMx)
+        } else {
+            (MadctlMx, MadctlMy)
+        };
+        if self.mirror_x {
+            madctl ^= flip_x as u8;
+        }
+        if self.mirror_y {
+            madctl ^= flip_y as u8;
+        }
+        if self.refresh_bottom_to_top {
+            madctl |= MadctlMl as u8;
+        }
+        if self.refresh_right_to_left {
+            madctl |= MadctlMh as u8;
+        }
+
+        madctl
+            | match self.color_order.unwrap_or(pcb_type.color_order()) {
+                ColorOrder::Rgb => MadctlRgb as u8,
+                ColorOrder::Bgr => MadctlBgr as u8,
+            }
+    }
+}
+
+impl Default for Orientation {
+    fn default() -> Self {
+        Self::new(Rotate::Degrees0)
+    }
+}
+
+impl From<Rotate> for Orientation {
+    fn from(rotation: Rotate) -> Self {
+        Self::new(rotation)
+    }
+}
@@ -0,0 +1,111 @@
+use crate::tft_display::{
+    canvas::Canvas, color::Color, dither::Dither, geometry::Rect, tft_display::Result,
+};
+
+/// Translated, clipped view of a part of a canvas
+///
+/// Coordinates are relative to the region's top left corner and nothing is drawn
+/// outside of it, so widgets can't overdraw their neighbours. Every call pushes the
+/// region as clip rectangle and pops it again, clips pushed on the canvas stay in effect.
+pub struct Region<'a, C: Canvas + ?Sized> {
+    canvas: &'a mut C,
+    rect: Rect,
+}
+
+impl<'a, C: Canvas + ?Sized> Region<'a, C> {
+    pub fn new(canvas: &'a mut C, rect: Rect) -> Self {
+        Self { canvas, rect }
+    }
+
+    /// Area of the region in the canvas' coordinates
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+
+    pub fn width(&self) -> u16 {
+        self.rect.w
+    }
+
+    pub fn height(&self) -> u16 {
+        self.rect.h
+    }
+
+    /// Nested region, `rect` relative to this one and clipped to it
+    pub fn subregion(&mut self, rect: Rect) -> Region<'_, C> {
+        let rect = self.to_parent(rect);
+        let rect = rect
+            .intersect(self.rect)
+            .unwrap_or(Rect::new(rect.x, rect.y, 0, 0));
+        Region::new(self.canvas, rect)
+    }
+
+    fn to_parent(&self, rect: Rect) -> Rect {
+        rect.translate(self.rect.x, self.rect.y)
+    }
+
+    fn clipped<T>(&mut self, draw: impl FnOnce(&mut C) -> T) -> T {
+        self.canvas.push_clip(self.rect);
+        let result = draw(self.canvas);
+        self.canvas.pop_clip();
+        result
+    }
+}
+
+impl<C: Canvas + ?Sized> Canvas for Region<'_, C> {
+    fn size(&self) -> (u16, u16) {
+        (self.rect.w, self.rect.h)
+    }
+
+    fn clip(&self) -> Rect {
+        let clip = self
+            .canvas
+            .clip()
+            .intersect(self.rect)
+            .unwrap_or(Rect::new(self.rect.x, self.rect.y, 0, 0));
+        clip.translate(-self.rect.x, -self.rect.y)
+    }
+
+    fn push_clip(&mut self, rect: Rect) {
+        let rect = self.to_parent(rect);
+        let clip = rect
+            .intersect(self.rect)
+            .unwrap_or(Rect::new(rect.x, rect.y, 0, 0));
+        self.canvas.push_clip(clip);
+    }
+
+    fn pop_clip(&mut self) -> Option<Rect> {
+        self.canvas
+            .pop_clip()
+            .map(|clip| clip.translate(-self.rect.x, -self.rect.y))
+    }
+
+    fn dither(&self) -> Dither {
+        self.canvas.dither()
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: Color) -> Result<()> {
+        let rect = self.to_parent(rect);
+        self.clipped(|canvas| canvas.fill_rect(rect, color))
+    }
+
+    fn draw_rows(&mut self, rect: Rect, row: &mut dyn FnMut(u16, &mut Vec<Color>)) -> Result<()> {
+        let rect = self.to_parent(rect);
+        self.clipped(|canvas| canvas.draw_rows(rect, row))
+    }
+
+    fn pixel(&self, x: i32, y: i32) -> Option<Color> {
+        let (x, y) = (x + self.rect.x, y + self.rect.y);
+        if !self.rect.contains(x, y) {
+            return None;
+        }
+        self.canvas.pixel(x, y)
+    }
+
+    fn blend_pixel(&mut self, x: i32, y: i32, color: Color, alpha: u8) -> Result<()> {
+        let (x, y) = (x + self.rect.x, y + self.rect.y);
+        if !self.rect.contains(x, y) {
+            return Ok(());
+        }
+        self.canvas.blend_pixel(x, y, color, alpha)
+    }
+}
@@ -0,0 +1,202 @@
+use crate::tft_display::{
+    canvas::Canvas, color::Color, dirty::DirtyRegion, framebuffer::Framebuffer, geometry::Rect,
+    sprite::Sprite, tft_display::Result, tft_display::TftDisplay, tilemap::TileMap,
+};
+
+/// What is drawn behind the sprites of a `Scene`
+pub enum Background {
+    Color(Color),
+    /// Tiles at the scene's top left corner, empty cells are black
+    TileMap(TileMap),
+    /// Pre-rendered picture the size of the scene
+    Framebuffer(Framebuffer),
+}
+
+/// Handle of a sprite added to a `Scene`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpriteId(usize);
+
+struct Placed {
+    sprite: Sprite,
+    x: i32,
+    y: i32,
+    visible: bool,
+}
+
+impl Placed {
+    fn rect(&self) -> Rect {
+        self.sprite.rect_at(self.x, self.y)
+    }
+}
+
+/// Movable sprites over a background, composited in a framebuffer
+///
+/// Changes only mark the areas they affect, `flush` recomposites those and sends
+/// just them to the display. Sprites are drawn in the order they were added.
+pub struct Scene {
+    frame: Framebuffer,
+    background: Background,
+    sprites: Vec<Option<Placed>>,
+    dirty: DirtyRegion,
+}
+
+impl Scene {
+    pub fn new(width: u16, height: u16, background: Background) -> Self {
+        let mut scene = Self {
+            frame: Framebuffer::new(width, height, Color::BLACK),
+            background,
+            sprites: Vec::new(),
+            dirty: DirtyRegion::new(),
+        };
+        scene.invalidate(scene.frame.bounds());
+        scene
+    }
+
+    pub fn add(&mut self, sprite: Sprite, x: i32, y: i32) -> SpriteId {
+        let placed = Placed {
+            sprite,
+            x,
+            y,
+            visible: true,
+        };
+        self.dirty.add(placed.rect());
+
+        // Slots of removed sprites aren't reused, so the new one is drawn on top and
+        // an old id can't reach it
+        self.sprites.push(Some(placed));
+        SpriteId(self.sprites.len() - 1)
+    }
+
+    pub fn remove(&mut self, id: SpriteId) -> Option<Sprite> {
+        let placed = self.sprites.get_mut(id.0)?.take()?;
+        self.dirty.add(placed.rect());
+        Some(placed.sprite)
+    }
+
+    pub fn move_to(&mut self, id: SpriteId, x: i32, y: i32) {
+        self.update(id, |placed| (placed.x, placed.y) = (x, y));
+    }
+
+    pub fn move_by(&mut self, id: SpriteId, dx: i32, dy: i32) {
+        self.update(id, |placed| {
+            (placed.x, placed.y) = (placed.x + dx, placed.y + dy)
+        });
+    }
+
+    /// Swaps the image of a sprite, e.g. to the next animation frame
+    pub fn set_sprite(&mut self, id: SpriteId, sprite: Sprite) {
+        self.update(id, |placed| placed.sprite = sprite);
+    }
+
+    pub fn set_visible(&mut self, id: SpriteId, visible: bool) {
+        self.update(id, |placed| placed.visible = visible);
+    }
+
+    pub fn position(&self, id: SpriteId) -> Option<(i32, i32)> {
+        self.placed(id).map(|placed| (placed.x, placed.y))
+    }
+
+    pub fn sprite(&self, id: SpriteId) -> Option<&Sprite> {
+        self.placed(id).map(|placed| &placed.sprite)
+    }
+
+    pub fn background(&self) -> &Background {
+        &self.background
+    }
+
+    /// Background for changes, tile map edits are picked up on the next flush
+    pub fn background_mut(&mut self) -> &mut Background {
+        &mut self.background
+    }
+
+    /// Replaces the background and redraws everything
+    pub fn set_background(&mut self, background: Background) {
+        self.background = background;
+        self.invalidate(self.frame.bounds());
+    }
+
+    /// Marks `rect` to be recomposited on the next flush
+    pub fn invalidate(&mut self, rect: Rect) {
+        self.dirty.add(rect);
+    }
+
+    /// The composited frame, current as of the last `render`
+    pub fn framebuffer(&self) -> &Framebuffer {
+        &self.frame
+    }
+
+    /// Recomposites the changed areas, returns them
+    pub fn render(&mut self) -> Result<Vec<Rect>> {
+        if let Background::TileMap(map) = &mut self.background {
+            if let Some(changed) = map.take_dirty() {
+                self.dirty.add(changed);
+            }
+        }
+
+        let rects = self.dirty.take(self.frame.bounds());
+        for &rect in &rects {
+            self.frame.push_clip(rect);
+            let result = self.compose(rect);
+            self.frame.pop_clip();
+            result?;
+        }
+        Ok(rects)
+    }
+
+    /// Renders and sends the changed areas to the display, after vertical blank if
+    /// the display has a `VSync`
+    pub fn flush(&mut self, display: &mut TftDisplay) -> Result<()> {
+        let rects = self.render()?;
+        if !rects.is_empty() {
+            display.wait_for_vblank()?;
+        }
+        for rect in rects {
+            display.flush_rect(&self.frame, rect)?;
+        }
+        Ok(())
+    }
+
+    fn compose(&mut self, rect: Rect) -> Result<()> {
+        match &self.background {
+            Background::Color(color) => self.frame.fill_rect(rect, *color)?,
+            Background::TileMap(map) => {
+                self.frame.fill_rect(rect, Color::BLACK)?;
+                map.draw_area(&mut self.frame, 0, 0, rect)?
+            }
+            Background::Framebuffer(picture) => self.frame.copy_from(picture, rect, rect.x, rect.y),
+        }
+
+        let visible = self
+            .sprites
+            .iter()
+            .flatten()
+            .filter(|placed| placed.visible);
+        for placed in visible {
+            if placed.rect().intersect(rect).is_some() {
+                self.frame.draw_sprite(&placed.sprite, placed.x, placed.y)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn placed(&self, id: SpriteId) -> Option<&Placed> {
+        self.sprites.get(id.0).and_then(Option::as_ref)
+    }
+
+    /// Changes a sprite, marking where it was and where it is now
+    fn update(&mut self, id: SpriteId, change: impl FnOnce(&mut Placed)) {
+        let Some(placed) = self.sprites.get_mut(id.0).and_then(Option::as_mut) else {
+            return;
+        };
+        let (before, visible) = (placed.rect(), placed.visible);
+        change(placed);
+
+        if visible {
+            self.dirty.add(before);
+        }
+        if placed.visible {
+            let after = placed.rect();
+            self.dirty.add(after);
+        }
+    }
+}
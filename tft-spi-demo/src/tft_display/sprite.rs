@@ -0,0 +1,185 @@
+use crate::tft_display::{
+    color::Color,
+    dither::{dither_rgb888, Dither},
+    error::Error,
+    geometry::Rect,
+    image::Image,
+    tft_display::Result,
+};
+
+/// Which pixels of a sprite are drawn
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Transparency {
+    /// Every pixel
+    #[default]
+    Opaque,
+    /// Every pixel that isn't this color
+    Key(Color),
+    /// Pixels whose mask entry is set, row by row
+    Mask(Vec<bool>),
+}
+
+/// Small image with transparent pixels, drawn with `Canvas::draw_sprite`
+///
+/// Transparent pixels are skipped rather than blended, so sprites draw the same on
+/// canvases that can't be read back.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sprite {
+    width: u16,
+    height: u16,
+    pixels: Vec<Color>,
+    transparency: Transparency,
+}
+
+impl Sprite {
+    /// Opaque sprite from `width` x `height` pixels, row by row
+    pub fn new(width: u16, height: u16, pixels: Vec<Color>) -> Result<Self> {
+        check_len(pixels.len(), width, height)?;
+        Ok(Self {
+            width,
+            height,
+            pixels,
+            transparency: Transparency::Opaque,
+        })
+    }
+
+    /// Sprite of a single color, e.g. a cursor block
+    pub fn filled(width: u16, height: u16, color: Color) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![color; width as usize * height as usize],
+            transparency: Transparency::Opaque,
+        }
+    }
+
+    /// Opaque sprite from a decoded image, quantized with `dither`
+    pub fn from_image(image: &Image, dither: Dither) -> Self {
+        let (width, height) = (image.width(), image.height());
+        Self {
+            width,
+            height,
+            pixels: dither_rgb888(0, 0, width, height, image.pixels(), dither),
+            transparency: Transparency::Opaque,
+        }
+    }
+
+    /// Skips every pixel of color `key`
+    pub fn with_color_key(mut self, key: Color) -> Self {
+        self.transparency = Transparency::Key(key);
+        self
+    }
+
+    /// Draws only pixels whose `mask` entry is set
+    pub fn with_mask(mut self, mask: Vec<bool>) -> Result<Self> {
+        check_len(mask.len(), self.width, self.height)?;
+        self.transparency = Transparency::Mask(mask);
+        Ok(self)
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    pub fn transparency(&self) -> &Transparency {
+        &self.transparency
+    }
+
+    /// Area covered when drawn at (`x`, `y`)
+    pub fn rect_at(&self, x: i32, y: i32) -> Rect {
+        Rect::new(x, y, self.width, self.height)
+    }
+
+    pub fn is_opaque(&self) -> bool {
+        self.transparency == Transparency::Opaque
+    }
+
+    /// Whether the pixel at (`x`, `y`) is drawn
+    pub fn is_visible(&self, x: u16, y: u16) -> bool {
+        let index = y as usize * self.width as usize + x as usize;
+        match &self.transparency {
+            Transparency::Opaque => true,
+            Transparency::Key(key) => self.pixels[index] != *key,
+            Transparency::Mask(mask) => mask[index],
+        }
+    }
+
+    /// Column ranges of visible pixels in row `y`
+    pub fn runs(&self, y: u16) -> impl Iterator<Item = (u16, u16)> + '_ {
+        let mut x = 0;
+        std::iter::from_fn(move || {
+            while x < self.width && !self.is_visible(x, y) {
+                x += 1;
+            }
+            let start = x;
+            while x < self.width && self.is_visible(x, y) {
+                x += 1;
+            }
+            (start < x).then_some((start, x))
+        })
+    }
+
+    /// Pixels of row `y`
+    pub fn row(&self, y: u16) -> &[Color] {
+        &self.pixels[y as usize * self.width as usize..][..self.width as usize]
+    }
+
+    /// Splits a sprite sheet into `frame_width` x `frame_height` frames, row by row
+    ///
+    /// Frames keep the sheet's transparency, partial frames at the edges are dropped.
+    pub fn frames(&self, frame_width: u16, frame_height: u16) -> Vec<Sprite> {
+        if frame_width == 0 || frame_height == 0 {
+            return Vec::new();
+        }
+
+        let mut frames = Vec::new();
+        for top in (0..self.height / frame_height).map(|row| row * frame_height) {
+            for left in (0..self.width / frame_width).map(|column| column * frame_width) {
+                frames.push(self.crop(left, top, frame_width, frame_height));
+            }
+        }
+        frames
+    }
+
+    /// Part of the sprite, which must lie inside it
+    fn crop(&self, x: u16, y: u16, width: u16, height: u16) -> Sprite {
+        let area = Rect::new(x as i32, y as i32, width, height);
+        let transparency = match &self.transparency {
+            Transparency::Mask(mask) => Transparency::Mask(crop(mask, self.width, area)),
+            other => other.clone(),
+        };
+
+        Sprite {
+            width,
+            height,
+            pixels: crop(&self.pixels, self.width, area),
+            transparency,
+        }
+    }
+}
+
+/// `area` of row by row `values` that are `stride` wide
+fn crop<T: Copy>(values: &[T], stride: u16, area: Rect) -> Vec<T> {
+    (area.y..area.bottom())
+        .flat_map(|row| {
+            let start = row as usize * stride as usize + area.x as usize;
+            values[start..start + area.w as usize].iter().copied()
+        })
+        .collect()
+}
+
+fn check_len(given: usize, width: u16, height: u16) -> Result<()> {
+    let expected = width as usize * height as usize;
+    if given != expected {
+        return Err(Error::Buffer { given, expected });
+    }
+    Ok(())
+}
@@ -1,16 +1,47 @@
 use crate::tft_display::{
+    canvas::Canvas,
     color::Color,
-    enums::{Command, MadControl, PcbType, Rotate},
+    dither::Dither,
+    enums::{Command, GradientDirection, MadControl, PcbType, TearingMode},
     error::Error,
+    fill::Pattern,
+    framebuffer::Framebuffer,
+    gamma::GammaCurve,
+    geometry::Rect,
+    image::{Filter, Image},
+    orientation::Orientation,
+    vsync::VSync,
 };
 use crate::tft_spi::{TftSpi, TftSpiImpl};
 
-use std::{result, thread, time::Duration};
+use std::{path::Path, result, thread, time::Duration};
 use rppal::spi;
 
 pub type Result<T> = result::Result<T, Error>;
 
-const _MAX_BUFFER_SIZE: usize = 3 *1024;
+/// Size of the buffers pixel data is staged in before it is sent, a whole number of
+/// pixels below the default spidev transfer limit
+const MAX_BUFFER_SIZE: usize = 3 *1024;
+
+/// Bytes per pixel in the 18-bit interface pixel format
+pub const BYTES_PER_PIXEL: usize = 3;
+
+/// Native GRAM size of the ILI9488 in its default portrait orientation
+const GRAM_WIDTH: u16 = 320;
+const GRAM_HEIGHT: u16 = 480;
+
+/// Vertical scroll area and how far its content is scrolled
+#[derive(Clone, Copy, Debug)]
+struct Scroll {
+    /// On screen, full width
+    area: Rect,
+    /// First GRAM row of the area (TFA)
+    first: u16,
+    /// GRAM rows the content is moved by, `0..area.h`
+    offset: u16,
+    /// Screen rows run opposite to GRAM rows (MY set)
+    flipped: bool,
+}
 
 pub struct TftDisplay {
     // tft_spi: Box<dyn TftSpi>,
@@ -22,44 +53,29 @@ pub struct TftDisplay {
     height: u16,
     start_width: u16,
     start_height: u16,
-    rotate: Rotate,
+    orientation: Orientation,
+
+    /// Column/row where the visible area starts in GRAM, in the unmirrored portrait
+    /// orientation
+    gram_offset: (u16, u16),
+
+    inverted: bool,
+    gamma: GammaCurve,
+    tearing: Option<TearingMode>,
+    /// TE line watcher flushes are timed with
+    vsync: Option<VSync>,
+    /// Hardware vertical scrolling, see `set_scroll_area`
+    scroll: Option<Scroll>,
+
+    /// Transparent image pixels are composited against this color
+    image_background: Color,
+    /// Dithering of 24-bit sources that don't take it per call
+    dither: Dither,
+
+    /// Clip rectangles, each already within the one below
+    clips: Vec<Rect>,
 }
 
-const _FONT: &[u8; 255] = &[
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5F, 0x00, 0x00, 0x00, 0x07, 0x00, 0x07, 0x00, 0x14,
-    0x7F, 0x14, 0x7F, 0x14, 0x24, 0x2A, 0x7F, 0x2A, 0x12, 0x23, 0x13, 0x08, 0x64, 0x62, 0x36, 0x49,
-    0x56, 0x20, 0x50, 0x00, 0x08, 0x07, 0x03, 0x00, 0x00, 0x1C, 0x22, 0x41, 0x00, 0x00, 0x41, 0x22,
-    0x1C, 0x00, 0x2A, 0x1C, 0x7F, 0x1C, 0x2A, 0x08, 0x08, 0x3E, 0x08, 0x08, 0x00, 0x80, 0x70, 0x30,
-    0x00, 0x08, 0x08, 0x08, 0x08, 0x08, 0x00, 0x00, 0x60, 0x60, 0x00, 0x20, 0x10, 0x08, 0x04, 0x02,
-    0x3E, 0x51, 0x49, 0x45, 0x3E, 0x00, 0x42, 0x7F, 0x40, 0x00, 0x72, 0x49, 0x49, 0x49, 0x46, 0x21,
-    0x41, 0x49, 0x4D, 0x33, 0x18, 0x14, 0x12, 0x7F, 0x10, 0x27, 0x45, 0x45, 0x45, 0x39, 0x3C, 0x4A,
-    0x49, 0x49, 0x31, 0x41, 0x21, 0x11, 0x09, 0x07, 0x36, 0x49, 0x49, 0x49, 0x36, 0x46, 0x49, 0x49,
-    0x29, 0x1E, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x40, 0x34, 0x00, 0x00, 0x00, 0x08, 0x14, 0x22,
-    0x41, 0x14, 0x14, 0x14, 0x14, 0x14, 0x00, 0x41, 0x22, 0x14, 0x08, 0x02, 0x01, 0x59, 0x09, 0x06,
-    0x3E, 0x41, 0x5D, 0x59, 0x4E, 0x7C, 0x12, 0x11, 0x12, 0x7C, 0x7F, 0x49, 0x49, 0x49, 0x36, 0x3E,
-    0x41, 0x41, 0x41, 0x22, 0x7F, 0x41, 0x41, 0x41, 0x3E, 0x7F, 0x49, 0x49, 0x49, 0x41, 0x7F, 0x09,
-    0x09, 0x09, 0x01, 0x3E, 0x41, 0x41, 0x51, 0x73, 0x7F, 0x08, 0x08, 0x08, 0x7F, 0x00, 0x41, 0x7F,
-    0x41, 0x00, 0x20, 0x40, 0x41, 0x3F, 0x01, 0x7F, 0x08, 0x14, 0x22, 0x41, 0x7F, 0x40, 0x40, 0x40,
-    0x40, 0x7F, 0x02, 0x1C, 0x02, 0x7F, 0x7F, 0x04, 0x08, 0x10, 0x7F, 0x3E, 0x41, 0x41, 0x41, 0x3E,
-    0x7F, 0x09, 0x09, 0x09, 0x06, 0x3E, 0x41, 0x51, 0x21, 0x5E, 0x7F, 0x09, 0x19, 0x29, 0x46,
-];
-const _FONT2: &[u8; 220] = &[
-    0x26, 0x49, 0x49, 0x49, 0x32, 0x03, 0x01, 0x7F, 0x01, 0x03, 0x3F, 0x40, 0x40, 0x40, 0x3F, 0x1F,
-    0x20, 0x40, 0x20, 0x1F, 0x3F, 0x40, 0x38, 0x40, 0x3F, 0x63, 0x14, 0x08, 0x14, 0x63, 0x03, 0x04,
-    0x78, 0x04, 0x03, 0x61, 0x59, 0x49, 0x4D, 0x43, 0x00, 0x7F, 0x41, 0x41, 0x41, 0x02, 0x04, 0x08,
-    0x10, 0x20, 0x00, 0x41, 0x41, 0x41, 0x7F, 0x04, 0x02, 0x01, 0x02, 0x04, 0x40, 0x40, 0x40, 0x40,
-    0x40, 0x00, 0x03, 0x07, 0x08, 0x00, 0x20, 0x54, 0x54, 0x78, 0x40, 0x7F, 0x28, 0x44, 0x44, 0x38,
-    0x38, 0x44, 0x44, 0x44, 0x28, 0x38, 0x44, 0x44, 0x28, 0x7F, 0x38, 0x54, 0x54, 0x54, 0x18, 0x00,
-    0x08, 0x7E, 0x09, 0x02, 0x18, 0xA4, 0xA4, 0x9C, 0x78, 0x7F, 0x08, 0x04, 0x04, 0x78, 0x00, 0x44,
-    0x7D, 0x40, 0x00, 0x20, 0x40, 0x40, 0x3D, 0x00, 0x7F, 0x10, 0x28, 0x44, 0x00, 0x00, 0x41, 0x7F,
-    0x40, 0x00, 0x7C, 0x04, 0x78, 0x04, 0x78, 0x7C, 0x08, 0x04, 0x04, 0x78, 0x38, 0x44, 0x44, 0x44,
-    0x38, 0xFC, 0x18, 0x24, 0x24, 0x18, 0x18, 0x24, 0x24, 0x18, 0xFC, 0x7C, 0x08, 0x04, 0x04, 0x08,
-    0x48, 0x54, 0x54, 0x54, 0x24, 0x04, 0x04, 0x3F, 0x44, 0x24, 0x3C, 0x40, 0x40, 0x20, 0x7C, 0x1C,
-    0x20, 0x40, 0x20, 0x1C, 0x3C, 0x40, 0x30, 0x40, 0x3C, 0x44, 0x28, 0x10, 0x28, 0x44, 0x4C, 0x90,
-    0x90, 0x90, 0x7C, 0x44, 0x64, 0x54, 0x4C, 0x44, 0x00, 0x08, 0x36, 0x41, 0x00, 0x00, 0x00, 0x77,
-    0x00, 0x00, 0x00, 0x41, 0x36, 0x08, 0x00, 0x02, 0x01, 0x02, 0x04, 0x02,
-];
-
 impl TftDisplay {
     // pub fn new(tft_spi: Box<dyn TftSpi>) -> Self {
     pub fn new(tft_spi: TftSpiImpl) -> Self {
@@ -74,117 +90,359 @@ impl TftDisplay {
             width: 320,
             start_height: 480,
             start_width: 320,
-            rotate: Rotate::Degrees0,
+            orientation: Orientation::default(),
+            gram_offset: (0, 0),
+            inverted: false,
+            gamma: GammaCurve::DEFAULT,
+            tearing: None,
+            vsync: None,
+            scroll: None,
+            image_background: Color::BLACK,
+            dither: Dither::None,
+            clips: Vec::new(),
         }
     }
 
-    pub fn init_screen_size(&mut self, _x_offset: u16, _y_offset: u16, width: u16, height: u16) {
+    /// Sets the visible screen size and where it starts in GRAM
+    ///
+    /// `x_offset`/`y_offset` are given in GRAM's own portrait layout, without any of the
+    /// MADCTL mirroring. The offsets used for drawing follow the MADCTL bits, see
+    /// `gram_offsets`.
+    pub fn init_screen_size(&mut self, x_offset: u16, y_offset: u16, width: u16, height: u16) {
         self.width = width;
         self.start_width = width;
         self.height = height;
         self.start_height = height;
+        self.gram_offset = (x_offset, y_offset);
     }
 
-    pub fn set_rotation(&mut self, rotate: Rotate) {
-        let madctrl: u8;
+    /// Sets rotation, mirroring and color order
+    ///
+    /// Accepts a plain `Rotate` as well as a full `Orientation`.
+    pub fn set_rotation(&mut self, orientation: impl Into<Orientation>) -> Result<()> {
+        let orientation = orientation.into();
+        let madctl = orientation.madctl(&self.pcb_type);
 
-        self.rotate = rotate;
-        match self.rotate {
-            Rotate::Degrees0 => {
-                if self.pcb_type == PcbType::Black {
-                    madctrl = MadControl::MadctlRgb as u8
-                        | MadControl::MadctlMx as u8
-                        | MadControl::MadctlMy as u8;
-                } else {
-                    madctrl = MadControl::MadctlBgr as u8
-                        | MadControl::MadctlMy as u8;
-                }
-                self.width = self.start_width;
-                self.height = self.start_height;
-            }
-            Rotate::Degrees90 => {
-                if self.pcb_type == PcbType::Black {
-                    madctrl = MadControl::MadctlRgb as u8
-                        | MadControl::MadctlMv as u8
-                        | MadControl::MadctlMy as u8;
-                } else {
-                    madctrl = MadControl::MadctlBgr as u8
-                        | MadControl::MadctlMv as u8;
-                }
-                self.width = self.start_height;
-                self.height = self.start_width;
-            }
-            Rotate::Degrees180 => {
-                if self.pcb_type == PcbType::Black {
-                    madctrl = MadControl::MadctlRgb as u8;
-                } else {
-                    madctrl = MadControl::MadctlBgr as u8
-                        | MadControl::MadctlMx as u8;
-                }
-                self.width = self.start_width;
-                self.height = self.start_height;
-            }
-            Rotate::Degrees270 => {
-                if self.pcb_type == PcbType::Black {
-                    madctrl = MadControl::MadctlRgb as u8
-                        | MadControl::MadctlMv as u8
-                        | MadControl::MadctlMx as u8;
-                } else {
-                    madctrl = MadControl::MadctlBgr as u8
-                        | MadControl::MadctlMv as u8
-                        | MadControl::MadctlMx as u8
-                        | MadControl::MadctlMy as u8;
-                }
-                self.width = self.start_height;
-                self.height = self.start_width;
+        // The scroll area is tied to the old orientation
+        if self.scroll.is_some() {
+            self.set_scroll_area(None)?;
+        }
+        self.tft_spi.write_reg(Command::MemoryAccessControl, &[madctl])?;
+
+        self.orientation = orientation;
+        if orientation.is_landscape() {
+            self.width = self.start_height;
+            self.height = self.start_width;
+        } else {
+            self.width = self.start_width;
+            self.height = self.start_height;
+        }
+
+        Ok(())
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// Turns color inversion on or off, kept across `initialize`
+    pub fn set_inverted(&mut self, inverted: bool) -> Result<()> {
+        self.write_inversion(inverted)?;
+        self.inverted = inverted;
+        Ok(())
+    }
+
+    pub fn is_inverted(&self) -> bool {
+        self.inverted
+    }
+
+    /// Validates and sends a gamma curve, kept across `initialize`
+    pub fn set_gamma(&mut self, gamma: GammaCurve) -> Result<()> {
+        gamma.validate()?;
+        self.write_gamma(&gamma)?;
+        self.gamma = gamma;
+        Ok(())
+    }
+
+    pub fn gamma(&self) -> GammaCurve {
+        self.gamma
+    }
+
+    /// Turns the tearing effect output on in `mode`, or off, kept across `initialize`
+    pub fn set_tearing_effect(&mut self, mode: Option<TearingMode>) -> Result<()> {
+        self.write_tearing(mode)?;
+        self.tearing = mode;
+        Ok(())
+    }
+
+    pub fn tearing_effect(&self) -> Option<TearingMode> {
+        self.tearing
+    }
+
+    /// Times `flush_synced` and `DoubleBuffer::present` with the TE line
+    ///
+    /// Turns the TE output on for vertical blanking if it is off.
+    pub fn set_vsync(&mut self, vsync: Option<VSync>) -> Result<()> {
+        if vsync.is_some() && self.tearing.is_none() {
+            self.set_tearing_effect(Some(TearingMode::VBlank))?;
+        }
+        self.vsync = vsync;
+        Ok(())
+    }
+
+    /// Lets the controller scroll `area` vertically, `None` ends scrolling
+    ///
+    /// Only full width areas in portrait orientations can be scrolled, returns `false`
+    /// for anything else. Drawing keeps using screen coordinates, rows inside the area
+    /// are redirected to where the scrolled GRAM shows them. Ending scrolling leaves
+    /// the area's rows rotated on screen until they are drawn again.
+    pub fn set_scroll_area(&mut self, area: Option<Rect>) -> Result<bool> {
+        let Some(area) = area else {
+            self.write_scroll_area(0, GRAM_HEIGHT)?;
+            self.tft_spi.write_reg(Command::VerticalScrollingStartAddress, &[0, 0])?;
+            self.scroll = None;
+            return Ok(true);
+        };
+
+        let fits = area.intersect(self.screen()) == Some(area);
+        if self.orientation.is_landscape() || !fits || area.x != 0 || area.w != self.width {
+            return Ok(false);
+        }
+
+        let madctl = self.orientation.madctl(&self.pcb_type);
+        let flipped = madctl & MadControl::MadctlMy as u8 != 0;
+        let row_offset = self.gram_offsets().1 as i32;
+        let gram_row = |y: i32| {
+            if flipped {
+                (GRAM_HEIGHT as i32 - 1 - (y + row_offset)) as u16
+            } else {
+                (y + row_offset) as u16
             }
+        };
+        let first = gram_row(area.y).min(gram_row(area.bottom() - 1));
+
+        self.write_scroll_area(first, area.h)?;
+        self.tft_spi
+            .write_reg(Command::VerticalScrollingStartAddress, &first.to_be_bytes())?;
+        self.scroll = Some(Scroll {
+            area,
+            first,
+            offset: 0,
+            flipped,
+        });
+        Ok(true)
+    }
+
+    pub fn scroll_area(&self) -> Option<Rect> {
+        self.scroll.map(|scroll| scroll.area)
+    }
+
+    /// Moves the content of the scroll area up by `dy` rows (down if negative)
+    ///
+    /// Rows scrolled out at one edge come back in at the other, draw over them. Does
+    /// nothing without a scroll area.
+    pub fn scroll_by(&mut self, dy: i32) -> Result<()> {
+        let Some(scroll) = &mut self.scroll else {
+            return Ok(());
+        };
+        let dy = if scroll.flipped { -dy } else { dy };
+        scroll.offset = (scroll.offset as i32 + dy).rem_euclid(scroll.area.h as i32) as u16;
+
+        let start = scroll.first + scroll.offset;
+        self.tft_spi
+            .write_reg(Command::VerticalScrollingStartAddress, &start.to_be_bytes())?;
+        Ok(())
+    }
+
+    /// Blocks until vertical blank starts, returns `false` at once without `VSync`
+    pub fn wait_for_vblank(&mut self) -> Result<bool> {
+        match &mut self.vsync {
+            Some(vsync) => vsync.wait(),
+            None => Ok(false),
         }
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
 
-        let _ = self.tft_spi.write_reg(Command::MemoryAccessControl, &[madctrl]);
+    /// The whole screen in the current orientation
+    pub fn screen(&self) -> Rect {
+        Rect::from_size(self.width, self.height)
     }
 
     pub fn fill_screen(&mut self, color: Color) -> Result<()> {
-        self.fill_rectangle(0, 0, self.width, self.height, color)
+        self.fill(color)
+    }
+
+    pub fn fill_rectangle(&mut self, x: u16, y: u16, w: u16, h: u16, color: Color) -> Result<()> {
+        self.fill_rect(Rect::new(x as i32, y as i32, w, h), color)
+    }
+
+    /// Draws a `w` x `h` block of pixels, row by row, clipped to the current clip
+    pub fn draw_image(&mut self, x: u16, y: u16, w: u16, h: u16, pixels: &[Color]) -> Result<()> {
+        self.draw_image_rect(Rect::new(x as i32, y as i32, w, h), pixels)
+    }
+
+    /// Fills a rectangle with a linear gradient, quantized with `dither`
+    #[allow(clippy::too_many_arguments)]
+    pub fn fill_rectangle_gradient(
+        &mut self,
+        x: u16,
+        y: u16,
+        w: u16,
+        h: u16,
+        from: Color,
+        to: Color,
+        direction: GradientDirection,
+        dither: Dither,
+    ) -> Result<()> {
+        let rect = Rect::new(x as i32, y as i32, w, h);
+        self.fill_gradient_rect(rect, from, to, direction, dither)
     }
 
-    pub fn fill_rectangle(
+    /// Fills a rectangle with a radial gradient from `inner` at its center to `outer`
+    /// at half its shorter side, quantized with `dither`
+    #[allow(clippy::too_many_arguments)]
+    pub fn fill_radial_gradient(
         &mut self,
         x: u16,
         y: u16,
-        mut w: u16,
-        mut h: u16,
-        color: Color,
+        w: u16,
+        h: u16,
+        inner: Color,
+        outer: Color,
+        dither: Dither,
     ) -> Result<()> {
-        if x >= self.width || y >= self.height {
+        let rect = Rect::new(x as i32, y as i32, w, h);
+        self.fill_radial_gradient_rect(rect, inner, outer, dither)
+    }
+
+    /// Fills a rectangle with a checkerboard or hatch pattern
+    pub fn fill_pattern(&mut self, x: u16, y: u16, w: u16, h: u16, pattern: Pattern) -> Result<()> {
+        self.fill_pattern_rect(Rect::new(x as i32, y as i32, w, h), pattern)
+    }
+
+    /// Draws a `w` x `h` block of pixels already in the interface format
+    /// (`BYTES_PER_PIXEL` per pixel), clipped to the current clip
+    pub fn draw_raw(&mut self, x: u16, y: u16, w: u16, h: u16, bytes: &[u8]) -> Result<()> {
+        self.draw_raw_rect(Rect::new(x as i32, y as i32, w, h), bytes)
+    }
+
+    /// Clipped version of `draw_raw`, rows are sent straight from `bytes` and
+    /// unclipped blocks in a single write
+    pub fn draw_raw_rect(&mut self, rect: Rect, bytes: &[u8]) -> Result<()> {
+        Self::check_len(bytes.len(), rect.w, rect.h, BYTES_PER_PIXEL)?;
+        let Some(visible) = self.visible(rect) else {
             return Ok(());
         };
 
-        if (x + w) > self.width {
-            w = self.width - x;
-        }
-        if (y + h) > self.height {
-            h = self.height - y;
-        }
+        let stride = rect.w as usize * BYTES_PER_PIXEL;
+        for (band, y) in self.bands(visible) {
+            self.start_write(band, y)?;
+
+            let skip_rows = (band.y - rect.y) as usize;
+            let rows = &bytes[skip_rows * stride..][..band.h as usize * stride];
 
-        let r = color.red();
-        let g = color.green();
-        let b = color.blue();
-        let mut data = Vec::new();
-        for _ in 0..w {
-            data.push(r);
-            data.push(g);
-            data.push(b);
+            if visible.w == rect.w {
+                self.tft_spi.write_data(rows)?;
+            } else {
+                let skip = (visible.x - rect.x) as usize * BYTES_PER_PIXEL;
+                let row_len = visible.w as usize * BYTES_PER_PIXEL;
+                for row in rows.chunks_exact(stride) {
+                    self.tft_spi.write_data(&row[skip..][..row_len])?;
+                }
+            }
         }
 
-        self.set_addr_window(x, y, w, h)?;
-        self.tft_spi.write_command(Command::MemoryWrite)?;
+        Ok(())
+    }
+
+    pub fn set_image_background(&mut self, color: Color) {
+        self.image_background = color;
+    }
+
+    /// Sets the dithering used for decoded images drawn without one
+    pub fn set_dither(&mut self, dither: Dither) {
+        self.dither = dither;
+    }
+
+    /// Decodes a BMP, PNG or QOI file and draws it with its top left corner at (`x`, `y`)
+    pub fn draw_image_file(&mut self, path: impl AsRef<Path>, x: u16, y: u16) -> Result<()> {
+        let image = Image::open(path, self.image_background)?;
+        self.draw_decoded_image(&image, x, y)
+    }
+
+    /// Decodes a BMP, PNG or QOI image and draws it with its top left corner at (`x`, `y`)
+    pub fn draw_image_bytes(&mut self, bytes: &[u8], x: u16, y: u16) -> Result<()> {
+        let image = Image::decode(bytes, self.image_background)?;
+        self.draw_decoded_image(&image, x, y)
+    }
 
-        let buffer = data.as_slice();
-        for _ in 0..h {
-            self.tft_spi.write_data(buffer)?;
+    pub fn draw_decoded_image(&mut self, image: &Image, x: u16, y: u16) -> Result<()> {
+        let rect = Rect::new(x as i32, y as i32, image.width(), image.height());
+        self.draw_rgb888_rect(rect, image.pixels(), self.dither)
+    }
+
+    /// Scales `image` to fit the `w` x `h` rectangle, keeping its aspect ratio, and
+    /// draws it centered in there
+    pub fn draw_image_fit(
+        &mut self,
+        image: &Image,
+        x: u16,
+        y: u16,
+        w: u16,
+        h: u16,
+        filter: Filter,
+    ) -> Result<()> {
+        if w == 0 || h == 0 {
+            return Ok(());
         }
+        let image = image.fit(w, h, filter);
+        let x = x + (w - image.width()) / 2;
+        let y = y + (h - image.height()) / 2;
+        self.draw_decoded_image(&image, x, y)
+    }
 
-        Ok(())
+    /// Draws a `w` x `h` block of 24-bit RGB pixels, clipped to the current clip
+    pub fn draw_rgb888(
+        &mut self,
+        x: u16,
+        y: u16,
+        w: u16,
+        h: u16,
+        pixels: &[[u8; 3]],
+        dither: Dither,
+    ) -> Result<()> {
+        self.draw_rgb888_rect(Rect::new(x as i32, y as i32, w, h), pixels, dither)
+    }
+
+    /// Sends the whole framebuffer, its top left corner at the screen's
+    pub fn flush(&mut self, framebuffer: &Framebuffer) -> Result<()> {
+        self.flush_rect(framebuffer, framebuffer.bounds())
+    }
+
+    /// Sends the framebuffer starting right after vertical blank
+    ///
+    /// Writes that start with the refresh trailing behind them don't shear as long as
+    /// they outpace it, which partial updates do more easily than the whole screen.
+    pub fn flush_synced(&mut self, framebuffer: &Framebuffer) -> Result<()> {
+        self.wait_for_vblank()?;
+        self.flush(framebuffer)
+    }
+
+    /// Sends `rect` of the framebuffer to the same place on screen
+    pub fn flush_rect(&mut self, framebuffer: &Framebuffer, rect: Rect) -> Result<()> {
+        let Some(rect) = rect.intersect(framebuffer.bounds()) else {
+            return Ok(());
+        };
+        self.draw_rows(rect, &mut |row, out| {
+            out.extend_from_slice(framebuffer.row(rect.x as u16, rect.y as u16 + row, rect.w));
+        })
     }
 
     pub fn set_addr_window(&mut self, x: u16, y: u16, w: u16, h: u16) -> Result<()> {
@@ -216,17 +474,110 @@ impl TftDisplay {
             });
         }
 
+        let (col_offset, row_offset) = self.gram_offsets();
+
         self.tft_spi.write_command(Command::ColumnAddressSet)?;
-        self.tft_spi.write_word(xs)?;
-        self.tft_spi.write_word(xe)?;
+        self.tft_spi.write_word(xs + col_offset)?;
+        self.tft_spi.write_word(xe + col_offset)?;
 
         self.tft_spi.write_command(Command::RowAddressSet)?;
-        self.tft_spi.write_word(ys)?;
-        self.tft_spi.write_word(ye)?;
+        self.tft_spi.write_word(ys + row_offset)?;
+        self.tft_spi.write_word(ye + row_offset)?;
 
         Ok(())
     }
 
+    /// Part of `rect` inside the screen and the current clip
+    fn visible(&self, rect: Rect) -> Option<Rect> {
+        rect.intersect(self.clip())
+    }
+
+    /// Sets the address window to an already clipped rectangle
+    fn set_window(&mut self, rect: Rect) -> Result<()> {
+        self.set_addr_window(rect.x as u16, rect.y as u16, rect.w, rect.h)
+    }
+
+    /// Sets the address window to `band` moved to row `y` and starts a `MemoryWrite`
+    fn start_write(&mut self, band: Rect, y: i32) -> Result<()> {
+        self.set_window(Rect { y, ..band })?;
+        self.tft_spi.write_command(Command::MemoryWrite)?;
+        Ok(())
+    }
+
+    /// Splits a visible rectangle into bands of rows that are contiguous in GRAM, each
+    /// with the screen row its address window starts at
+    ///
+    /// Without a scroll area that is the rectangle itself.
+    fn bands(&self, rect: Rect) -> Vec<(Rect, i32)> {
+        let Some(scroll) = self.scroll else {
+            return vec![(rect, rect.y)];
+        };
+
+        let mut bands: Vec<(Rect, i32)> = Vec::new();
+        for y in rect.y..rect.bottom() {
+            let target = self.scrolled_row(&scroll, y);
+            match bands.last_mut() {
+                Some((band, start)) if *start + band.h as i32 == target => band.h += 1,
+                _ => bands.push((Rect { y, h: 1, ..rect }, target)),
+            }
+        }
+        bands
+    }
+
+    /// Screen row whose address holds what is shown at row `y` while scrolled
+    fn scrolled_row(&self, scroll: &Scroll, y: i32) -> i32 {
+        let area = scroll.area;
+        if y < area.y || y >= area.bottom() {
+            return y;
+        }
+
+        let row_offset = self.gram_offsets().1 as i32;
+        let flip = |row: i32| {
+            if scroll.flipped {
+                GRAM_HEIGHT as i32 - 1 - row
+            } else {
+                row
+            }
+        };
+        let first = scroll.first as i32;
+        let shown = flip(y + row_offset) - first + scroll.offset as i32;
+        flip(first + shown.rem_euclid(area.h as i32)) - row_offset
+    }
+
+    fn check_len(given: usize, w: u16, h: u16, bytes_per_pixel: usize) -> Result<()> {
+        let expected = w as usize * h as usize * bytes_per_pixel;
+        if given != expected {
+            return Err(Error::Buffer { given, expected });
+        }
+        Ok(())
+    }
+
+    /// Column/row address offset of the visible area for the MADCTL value in use
+    ///
+    /// MX and MY reverse GRAM's columns and rows, which moves the offset to the far
+    /// edge. With MV the column address runs along GRAM's rows, so the offsets swap.
+    fn gram_offsets(&self) -> (u16, u16) {
+        let madctl = self.orientation.madctl(&self.pcb_type);
+        let is_set = |flag: MadControl| madctl & flag as u8 != 0;
+
+        let (x_offset, y_offset) = self.gram_offset;
+        let x_offset = if is_set(MadControl::MadctlMx) {
+            GRAM_WIDTH.saturating_sub(x_offset + self.start_width)
+        } else {
+            x_offset
+        };
+        let y_offset = if is_set(MadControl::MadctlMy) {
+            GRAM_HEIGHT.saturating_sub(y_offset + self.start_height)
+        } else {
+            y_offset
+        };
+        if is_set(MadControl::MadctlMv) {
+            (y_offset, x_offset)
+        } else {
+            (x_offset, y_offset)
+        }
+    }
+
     // pub fn set_cursor(&mut self) -> io::Result<()> {
     //     Ok(())
     // }
@@ -239,6 +590,9 @@ impl TftDisplay {
         self.reset_pin();
         self.init_display()?;
 
+        // The hardware reset ends scrolling
+        self.scroll = None;
+
         self.pcb_type = PcbType::None;
         Ok(())
     }
@@ -402,14 +756,8 @@ impl TftDisplay {
         self.tft_spi.write_reg(Command::InterfacePixelFormat, &[(dpi << 4) | dbi])?;
         self.tft_spi.write_reg(Command::InterfaceModeControl, &[0x00])?;
 
-        self.tft_spi.write_reg(Command::PositiveGammaControl, &[
-            0x0F, 0x1F, 0x1C, 0x0C, 0x0F, 0x08, 0x48, 0x98,
-            0x37, 0x0A, 0x13, 0x04, 0x11, 0x0D, 0x00,
-        ])?;
-        self.tft_spi.write_reg(Command::NegativeGammaControl, &[
-            0x0F, 0x32, 0x2E, 0x0B, 0x0D, 0x05, 0x47, 0x75,
-            0x37, 0x06, 0x10, 0x03, 0x24, 0x20, 0x00,
-        ])?;
+        let gamma = self.gamma;
+        self.write_gamma(&gamma)?;
         self.tft_spi.write_reg(Command::DigitalGammaControl1, &[
             0x0F, 0x32, 0x2E, 0x0B, 0x0D, 0x05, 0x47, 0x75,
             0x37, 0x06, 0x10, 0x03, 0x24, 0x20, 0x00,
@@ -421,12 +769,61 @@ impl TftDisplay {
         // self.tft_spi.write_reg(Command::DisplayFunctionControl, &[0, 2, display_height])?;
         // self.tft_spi.write_reg(Command::MemoryAccessControl, &[0x48])?;
 
+        // Soft reset turns inversion and TE off, restore what was configured
+        if self.inverted {
+            self.write_inversion(true)?;
+        }
+        if self.tearing.is_some() {
+            self.write_tearing(self.tearing)?;
+        }
+
         self.tft_spi.write_command_delay(Command::SleepOut, Duration::from_millis(120))?;
         self.tft_spi.write_command_delay(Command::DisplayOn, Duration::from_millis(100))?;
 
         Ok(())
     }
 
+    fn write_inversion(&mut self, inverted: bool) -> spi::Result<usize> {
+        if inverted {
+            self.tft_spi.write_command(Command::DisplayInversionOn)
+        } else {
+            self.tft_spi.write_command(Command::DisplayInversionOff)
+        }
+    }
+
+    fn write_tearing(&mut self, mode: Option<TearingMode>) -> spi::Result<usize> {
+        match mode {
+            Some(mode) => self.tft_spi.write_reg(Command::TearingEffectLineOn, &[mode as u8]),
+            None => self.tft_spi.write_command(Command::TearingEffectLineOff),
+        }
+    }
+
+    /// VSCRDEF: fixed rows above, `height` scrolling rows from GRAM row `first`, the
+    /// rest fixed below
+    fn write_scroll_area(&mut self, first: u16, height: u16) -> Result<()> {
+        let bottom = GRAM_HEIGHT
+            .checked_sub(first)
+            .and_then(|rest| rest.checked_sub(height))
+            .ok_or(Error::Size {
+                given: first.saturating_add(height),
+                max: GRAM_HEIGHT,
+            })?;
+        let [first_hi, first_lo] = first.to_be_bytes();
+        let [height_hi, height_lo] = height.to_be_bytes();
+        let [bottom_hi, bottom_lo] = bottom.to_be_bytes();
+        self.tft_spi.write_reg(
+            Command::VerticalScrollingDefinition,
+            &[first_hi, first_lo, height_hi, height_lo, bottom_hi, bottom_lo],
+        )?;
+        Ok(())
+    }
+
+    fn write_gamma(&mut self, gamma: &GammaCurve) -> spi::Result<()> {
+        self.tft_spi.write_reg(Command::PositiveGammaControl, &gamma.positive)?;
+        self.tft_spi.write_reg(Command::NegativeGammaControl, &gamma.negative)?;
+        Ok(())
+    }
+
     // fn _cmd3(&self) -> io::Result<()> {
     //     Ok(())
     // }
@@ -436,6 +833,104 @@ impl TftDisplay {
     }
 }
 
+impl Canvas for TftDisplay {
+    fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    fn clip(&self) -> Rect {
+        let screen = self.screen();
+        match self.clips.last() {
+            Some(clip) => clip.intersect(screen).unwrap_or(Rect::new(clip.x, clip.y, 0, 0)),
+            None => screen,
+        }
+    }
+
+    fn push_clip(&mut self, rect: Rect) {
+        let clip = self
+            .clip()
+            .intersect(rect)
+            .unwrap_or(Rect::new(rect.x, rect.y, 0, 0));
+        self.clips.push(clip);
+    }
+
+    fn pop_clip(&mut self) -> Option<Rect> {
+        self.clips.pop()
+    }
+
+    fn dither(&self) -> Dither {
+        self.dither
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: Color) -> Result<()> {
+        let Some(rect) = self.visible(rect) else {
+            return Ok(());
+        };
+
+        // Stage as many whole rows as fit in the buffer
+        let row_len = rect.w as usize * BYTES_PER_PIXEL;
+        let rows = (MAX_BUFFER_SIZE / row_len).clamp(1, rect.h as usize);
+        let pixel = [color.red(), color.green(), color.blue()];
+        let data = pixel.repeat(rect.w as usize * rows);
+
+        for (band, y) in self.bands(rect) {
+            self.start_write(band, y)?;
+
+            let mut remaining = band.h as usize;
+            while remaining > 0 {
+                let batch = remaining.min(rows);
+                self.tft_spi.write_data(&data[..batch * row_len])?;
+                remaining -= batch;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Streams `rect` in one `MemoryWrite`
+    ///
+    /// `row` appends all `rect.w` pixels of the given row, only rows and columns that
+    /// are visible are requested and sent, staged in `MAX_BUFFER_SIZE` pieces.
+    fn draw_rows(&mut self, rect: Rect, row: &mut dyn FnMut(u16, &mut Vec<Color>)) -> Result<()> {
+        let Some(visible) = self.visible(rect) else {
+            return Ok(());
+        };
+        let skip_x = (visible.x - rect.x) as usize;
+        let skip_y = (visible.y - rect.y) as u16;
+
+        let mut colors = Vec::with_capacity(rect.w as usize);
+        let mut data = Vec::with_capacity(MAX_BUFFER_SIZE);
+
+        for (band, y) in self.bands(visible) {
+            self.start_write(band, y)?;
+
+            let first = skip_y + (band.y - visible.y) as u16;
+            for index in first..first + band.h {
+                colors.clear();
+                row(index, &mut colors);
+                for color in colors.iter().skip(skip_x).take(visible.w as usize) {
+                    if data.len() == MAX_BUFFER_SIZE {
+                        self.tft_spi.write_data(&data)?;
+                        data.clear();
+                    }
+                    data.extend_from_slice(&[color.red(), color.green(), color.blue()]);
+                }
+            }
+            if !data.is_empty() {
+                self.tft_spi.write_data(&data)?;
+                data.clear();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// GRAM can't be read back over this interface
+    fn pixel(&self, _x: i32, _y: i32) -> Option<Color> {
+        None
+    }
+}
+
 // impl Default for TftDisplay {
 //     fn default() -> Self {
 //         Self::new()
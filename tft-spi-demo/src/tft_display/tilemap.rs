@@ -0,0 +1,187 @@
+use crate::tft_display::{
+    canvas::Canvas, error::Error, geometry::Rect, sprite::Sprite, tft_display::Result,
+};
+
+/// Grid of tiles from a shared tile set, e.g. a game level or a background
+///
+/// Changing a cell marks its area dirty until `take_dirty`, so only changed tiles
+/// need to be drawn again.
+#[derive(Clone, Debug)]
+pub struct TileMap {
+    tiles: Vec<Sprite>,
+    tile_width: u16,
+    tile_height: u16,
+    columns: u16,
+    rows: u16,
+    /// Tile index of every cell row by row, `None` for empty cells
+    cells: Vec<Option<usize>>,
+    dirty: Option<Rect>,
+}
+
+impl TileMap {
+    /// Empty `columns` x `rows` map, tiles must all have the size of the first
+    pub fn new(tiles: Vec<Sprite>, columns: u16, rows: u16) -> Result<Self> {
+        let (tile_width, tile_height) = tiles
+            .first()
+            .map_or((0, 0), |tile| (tile.width(), tile.height()));
+        if let Some(tile) = tiles
+            .iter()
+            .find(|tile| (tile.width(), tile.height()) != (tile_width, tile_height))
+        {
+            return Err(Error::Buffer {
+                given: tile.pixels().len(),
+                expected: tile_width as usize * tile_height as usize,
+            });
+        }
+
+        let mut map = Self {
+            tiles,
+            tile_width,
+            tile_height,
+            columns,
+            rows,
+            cells: vec![None; columns as usize * rows as usize],
+            dirty: None,
+        };
+        map.invalidate();
+        Ok(map)
+    }
+
+    /// Map cut from a tile sheet of `tile_width` x `tile_height` tiles
+    pub fn from_sheet(
+        sheet: &Sprite,
+        tile_width: u16,
+        tile_height: u16,
+        columns: u16,
+        rows: u16,
+    ) -> Result<Self> {
+        Self::new(sheet.frames(tile_width, tile_height), columns, rows)
+    }
+
+    pub fn columns(&self) -> u16 {
+        self.columns
+    }
+
+    pub fn rows(&self) -> u16 {
+        self.rows
+    }
+
+    pub fn tile_size(&self) -> (u16, u16) {
+        (self.tile_width, self.tile_height)
+    }
+
+    /// Size of the whole map in pixels
+    pub fn size(&self) -> (u16, u16) {
+        (
+            self.columns.saturating_mul(self.tile_width),
+            self.rows.saturating_mul(self.tile_height),
+        )
+    }
+
+    pub fn tile(&self, column: u16, row: u16) -> Option<usize> {
+        self.index(column, row).and_then(|index| self.cells[index])
+    }
+
+    /// Sets a cell, returns whether it changed
+    ///
+    /// Cells outside the map and unknown tiles are ignored.
+    pub fn set_tile(&mut self, column: u16, row: u16, tile: Option<usize>) -> bool {
+        let Some(index) = self.index(column, row) else {
+            return false;
+        };
+        if tile.is_some_and(|tile| tile >= self.tiles.len()) || self.cells[index] == tile {
+            return false;
+        }
+
+        self.cells[index] = tile;
+        let cell = self.cell_rect(column, row);
+        self.dirty = Some(self.dirty.map_or(cell, |dirty| dirty.union(cell)));
+        true
+    }
+
+    /// Sets every cell from row by row tile indices
+    pub fn set_tiles(&mut self, cells: &[Option<usize>]) -> Result<()> {
+        if cells.len() != self.cells.len() {
+            return Err(Error::Buffer {
+                given: cells.len(),
+                expected: self.cells.len(),
+            });
+        }
+        for (index, &tile) in cells.iter().enumerate() {
+            let (column, row) = (index % self.columns as usize, index / self.columns as usize);
+            self.set_tile(column as u16, row as u16, tile);
+        }
+        Ok(())
+    }
+
+    /// Pixel area of a cell, relative to the map's top left corner
+    pub fn cell_rect(&self, column: u16, row: u16) -> Rect {
+        Rect::new(
+            column as i32 * self.tile_width as i32,
+            row as i32 * self.tile_height as i32,
+            self.tile_width,
+            self.tile_height,
+        )
+    }
+
+    /// Marks the whole map as changed
+    pub fn invalidate(&mut self) {
+        let (w, h) = self.size();
+        self.dirty = Some(Rect::from_size(w, h));
+    }
+
+    /// Area changed since the last call, relative to the map's top left corner
+    pub fn take_dirty(&mut self) -> Option<Rect> {
+        self.dirty.take().filter(|dirty| !dirty.is_empty())
+    }
+
+    /// Draws the map with its top left corner at (`x`, `y`)
+    pub fn draw<C: Canvas + ?Sized>(&self, canvas: &mut C, x: i32, y: i32) -> Result<()> {
+        let (w, h) = self.size();
+        self.draw_area(canvas, x, y, Rect::from_size(w, h))
+    }
+
+    /// Draws the tiles overlapping `area` of the map, clipped to it
+    pub fn draw_area<C: Canvas + ?Sized>(
+        &self,
+        canvas: &mut C,
+        x: i32,
+        y: i32,
+        area: Rect,
+    ) -> Result<()> {
+        if self.tile_width == 0 || self.tile_height == 0 {
+            return Ok(());
+        }
+        let (w, h) = self.size();
+        let Some(area) = area.intersect(Rect::from_size(w, h)) else {
+            return Ok(());
+        };
+
+        let columns = area.x / self.tile_width as i32..=(area.right() - 1) / self.tile_width as i32;
+        let rows = area.y / self.tile_height as i32..=(area.bottom() - 1) / self.tile_height as i32;
+
+        canvas.push_clip(area.translate(x, y));
+        let result = rows
+            .flat_map(|row| {
+                columns
+                    .clone()
+                    .map(move |column| (column as u16, row as u16))
+            })
+            .try_for_each(|(column, row)| {
+                let Some(tile) = self.tile(column, row) else {
+                    return Ok(());
+                };
+                let cell = self.cell_rect(column, row);
+                canvas.draw_sprite(&self.tiles[tile], x + cell.x, y + cell.y)
+            });
+        canvas.pop_clip();
+        result
+    }
+
+    fn index(&self, column: u16, row: u16) -> Option<usize> {
+        if column >= self.columns || row >= self.rows {
+            return None;
+        }
+        Some(row as usize * self.columns as usize + column as usize)
+    }
+}
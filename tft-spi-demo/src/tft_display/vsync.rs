@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use rppal::gpio::{Gpio, InputPin, Trigger};
+
+use crate::tft_display::tft_display::Result;
+
+/// Longest wait for a TE pulse, a bit over one frame at the slowest frame rate
+const FRAME_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Watches the controller's tearing effect (TE) output on a GPIO pin
+///
+/// With `TearingMode::VBlank` the line rises once per frame as the panel starts
+/// vertical blanking, the best moment to start writing GRAM.
+pub struct VSync {
+    pin: InputPin,
+}
+
+impl VSync {
+    /// Watches BCM GPIO `pin`, which has to be wired to the TE pad of the panel
+    pub fn new(pin: u8) -> Result<Self> {
+        let mut pin = Gpio::new()?.get(pin)?.into_input_pulldown();
+        pin.set_interrupt(Trigger::RisingEdge)?;
+        Ok(Self { pin })
+    }
+
+    /// Blocks until the next vertical blank starts
+    ///
+    /// Returns `false` if no pulse came within a frame, e.g. when TE is off.
+    pub fn wait(&mut self) -> Result<bool> {
+        // Reset so an edge from an earlier frame doesn't count
+        Ok(self
+            .pin
+            .poll_interrupt(true, Some(FRAME_TIMEOUT))?
+            .is_some())
+    }
+}
@@ -1,7 +1,7 @@
 extern crate rppal;
 
 use std::sync::{Arc, Mutex};
-use std::{thread, time::Duration};
+use std::{fs, thread, time::Duration};
 
 use rppal::{
     gpio::{Gpio, OutputPin},
@@ -9,19 +9,22 @@ use rppal::{
 };
 
 use crate::tft_display::enums::Command;
-//use crate::util::MutexExt;
+use crate::util::MutexExt;
 //use dyn_clonable::clonable;
 
 //#[clonable]
 pub trait TftSpi: Clone + Sized {
-    fn reset_pin(self: &mut Self);
-    fn select_display(self: &mut Self);
-    fn write_reg(self: &mut Self, cmd: Command, data: &[u8]) -> spi::Result<usize>;
-    fn write_command(self: &mut Self, cmd: Command) -> spi::Result<usize>;
-    fn write_command_delay(self: &mut Self, cmd: Command, delay: Duration) -> spi::Result<usize>;
-    fn write_data(self: &mut Self, data: &[u8]) -> spi::Result<usize>;
-    fn write_data_delay(self: &mut Self, data: &[u8], delay: Duration) -> spi::Result<usize>;
-    fn write_word(self: &mut Self, word: u16) -> spi::Result<usize>;
+    fn reset_pin(&mut self);
+    fn select_display(&mut self);
+    fn write_reg(&mut self, cmd: Command, data: &[u8]) -> spi::Result<usize>;
+    fn write_command(&mut self, cmd: Command) -> spi::Result<usize>;
+    fn write_command_delay(&mut self, cmd: Command, delay: Duration) -> spi::Result<usize>;
+    fn write_data(&mut self, data: &[u8]) -> spi::Result<usize>;
+    fn write_data_delay(&mut self, data: &[u8], delay: Duration) -> spi::Result<usize>;
+    fn write_word(&mut self, word: u16) -> spi::Result<usize>;
+    fn max_transfer_size(&self) -> usize;
+    fn set_max_transfer_size(&mut self, size: usize);
+    fn read_touch(&mut self, command: u8) -> spi::Result<u16>;
 }
 
 #[derive(Clone)]
@@ -37,43 +40,64 @@ impl TftSpiImpl {
     }
 }
 
+impl Default for TftSpiImpl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TftSpi for TftSpiImpl {
     fn reset_pin(&mut self) {
-        self.inner.lock().unwrap().reset_pin()
+        self.inner.xlock().reset_pin()
     }
 
     fn select_display(&mut self) {
-        self.inner.lock().unwrap().select_display()
+        self.inner.xlock().select_display()
     }
 
     fn write_reg(&mut self, cmd: Command, data: &[u8]) -> spi::Result<usize> {
-        self.inner.lock().unwrap().write_reg(cmd, data)
+        self.inner.xlock().write_reg(cmd, data)
     }
 
     fn write_command(&mut self, cmd: Command) -> spi::Result<usize> {
-        self.inner.lock().unwrap().write_command(cmd)
+        self.inner.xlock().write_command(cmd)
     }
 
     fn write_command_delay(&mut self, cmd: Command, delay: Duration) -> spi::Result<usize> {
-        self.inner.lock().unwrap().write_command_delay(cmd, delay)
+        self.inner.xlock().write_command_delay(cmd, delay)
     }
 
     fn write_data(&mut self, data: &[u8]) -> spi::Result<usize> {
-        self.inner.lock().unwrap().write_data(data)
+        self.inner.xlock().write_data(data)
     }
 
     fn write_data_delay(&mut self, data: &[u8], delay: Duration) -> spi::Result<usize> {
-        self.inner.lock().unwrap().write_data_delay(data, delay)
+        self.inner.xlock().write_data_delay(data, delay)
     }
 
     fn write_word(&mut self, word: u16) -> spi::Result<usize> {
-        self.inner.lock().unwrap().write_word(word)
+        self.inner.xlock().write_word(word)
+    }
+
+    fn max_transfer_size(&self) -> usize {
+        self.inner.xlock().max_transfer_size()
+    }
+
+    fn set_max_transfer_size(&mut self, size: usize) {
+        self.inner.xlock().set_max_transfer_size(size)
+    }
+
+    fn read_touch(&mut self, command: u8) -> spi::Result<u16> {
+        self.inner.xlock().read_touch(command)
     }
 }
 
 struct InnerTftSpi {
     // cmd_buffer: [u8; mem::size_of::<u16>()],
     spi_device: Spi,
+    max_transfer: usize,
+    /// Largest transfer spidev accepts
+    bufsiz: usize,
     command: bool,
     display: bool,
     tft_dc: OutputPin,
@@ -84,6 +108,7 @@ struct InnerTftSpi {
 
 impl InnerTftSpi {
     const SPI_CLOCK_SPEED: u32 = 31_200_000; // max 26 MHz
+    const TOUCH_CLOCK_SPEED: u32 = 2_000_000; // XPT2046 max 2.5 MHz
 
     /// Reset
     /// GPIO 25
@@ -98,6 +123,10 @@ impl InnerTftSpi {
     const TFT_CS_DISPLAY: u8 = 8;
     const TFT_CS_TOUCH: u8 = 7;
 
+    /// Default `bufsiz` of the Linux spidev driver, the largest single transfer
+    const SPIDEV_BUFSIZ: usize = 4096;
+    const SPIDEV_BUFSIZ_PATH: &'static str = "/sys/module/spidev/parameters/bufsiz";
+
     pub fn new() -> Self {
         let spi = Self::create_spi().unwrap();
 
@@ -111,9 +140,12 @@ impl InnerTftSpi {
         tft_cs_display.set_high();
         tft_cs_touch.set_high();
 
+        let bufsiz = Self::read_bufsiz().unwrap_or(Self::SPIDEV_BUFSIZ);
         Self {
             // cmd_buffer: [0; mem::size_of::<u16>()],
             spi_device: spi,
+            max_transfer: bufsiz,
+            bufsiz,
             command: false,
             display: false,
             tft_dc,
@@ -157,6 +189,13 @@ impl InnerTftSpi {
         Ok(spi)
     }
 
+    /// Reads the spidev `bufsiz` module parameter, e.g. raised with
+    /// `spidev.bufsiz=65536` in `/boot/cmdline.txt`
+    fn read_bufsiz() -> Option<usize> {
+        let bufsiz = fs::read_to_string(Self::SPIDEV_BUFSIZ_PATH).ok()?;
+        bufsiz.trim().parse().ok().filter(|&size| size > 0)
+    }
+
     pub fn reset_pin(&mut self) {
         self.tft_rst.set_high();
         thread::sleep(Duration::from_millis(120));
@@ -174,6 +213,30 @@ impl InnerTftSpi {
         }
     }
 
+    /// Sends an XPT2046 control byte and reads back its 12-bit conversion
+    ///
+    /// Runs at the touch controller's lower clock, the display is selected again on
+    /// its next write.
+    pub fn read_touch(&mut self, command: u8) -> spi::Result<u16> {
+        self.display = false;
+        self.tft_cs_display.set_high();
+        self.tft_cs_touch.set_low();
+
+        // Chip select and clock are restored before any error is returned
+        let mut read = [0; 3];
+        let result = self
+            .spi_device
+            .set_clock_speed(Self::TOUCH_CLOCK_SPEED)
+            .and_then(|()| self.spi_device.transfer(&mut read, &[command, 0, 0]));
+        let restored = self.spi_device.set_clock_speed(Self::SPI_CLOCK_SPEED);
+        self.tft_cs_touch.set_high();
+        result?;
+        restored?;
+
+        // The 12 result bits follow the busy bit, MSB first
+        Ok(u16::from_be_bytes([read[1], read[2]]) >> 3)
+    }
+
     // pub fn write_reg(&mut self, cmd: Command, data: &[u8]) {
     //     self.write_command(cmd)?;
     //
@@ -193,6 +256,7 @@ impl InnerTftSpi {
     }
 
     pub fn write_command(&mut self, cmd: Command) -> spi::Result<usize> {
+        self.select_display();
         self.dc_set_low();
         self.spi_device.write(&[cmd as u8]) // self.cmd_buffer)
     }
@@ -206,9 +270,16 @@ impl InnerTftSpi {
         result
     }
 
+    /// Writes `data`, split into transfers of at most `max_transfer` bytes
     pub fn write_data(&mut self, data: &[u8]) -> spi::Result<usize> {
+        self.select_display();
         self.dc_set_high();
-        self.spi_device.write(data)
+
+        let mut bytes = 0;
+        for chunk in data.chunks(self.max_transfer) {
+            bytes += self.spi_device.write(chunk)?;
+        }
+        Ok(bytes)
     }
 
     pub fn write_data_delay(&mut self, data: &[u8], delay: Duration) -> spi::Result<usize> {
@@ -224,6 +295,17 @@ impl InnerTftSpi {
         self.write_data(&value.to_be_bytes())
     }
 
+    pub fn max_transfer_size(&self) -> usize {
+        self.max_transfer
+    }
+
+    /// Lowers the transfer size read from spidev
+    ///
+    /// Clamped to spidev's `bufsiz`, larger transfers would fail.
+    pub fn set_max_transfer_size(&mut self, size: usize) {
+        self.max_transfer = size.clamp(1, self.bufsiz);
+    }
+
     fn dc_set_low(&mut self) {
         if !self.command {
             self.command = true;
@@ -0,0 +1,71 @@
+/// Maps raw 12-bit touch readings to portrait screen coordinates
+///
+/// An affine transform, so it also covers swapped or mirrored axes and a slightly
+/// rotated touch layer: `x = a * raw_x + b * raw_y + c`, `y = d * raw_x + e * raw_y + f`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Calibration {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Calibration {
+    /// Linear mapping of raw readings from `min` to `max` onto a `width` x `height`
+    /// portrait screen
+    pub fn linear(min: (u16, u16), max: (u16, u16), width: u16, height: u16) -> Self {
+        let scale_x = width as f32 / (max.0 as f32 - min.0 as f32);
+        let scale_y = height as f32 / (max.1 as f32 - min.1 as f32);
+        Self {
+            a: scale_x,
+            b: 0.0,
+            c: -(min.0 as f32) * scale_x,
+            d: 0.0,
+            e: scale_y,
+            f: -(min.1 as f32) * scale_y,
+        }
+    }
+
+    /// Solves the transform from three touches of known screen points
+    ///
+    /// `None` if the points lie on one line. Pick them far apart, e.g. near three
+    /// corners.
+    pub fn from_points(raw: [(u16, u16); 3], screen: [(i32, i32); 3]) -> Option<Self> {
+        let [(x0, y0), (x1, y1), (x2, y2)] = raw.map(|(x, y)| (x as f32, y as f32));
+        let determinant = x0 * (y1 - y2) + x1 * (y2 - y0) + x2 * (y0 - y1);
+        if determinant.abs() < f32::EPSILON {
+            return None;
+        }
+
+        // Cramer's rule for both output axes
+        let solve = |[s0, s1, s2]: [f32; 3]| {
+            let a = (s0 * (y1 - y2) + s1 * (y2 - y0) + s2 * (y0 - y1)) / determinant;
+            let b = (x0 * (s1 - s2) + x1 * (s2 - s0) + x2 * (s0 - s1)) / determinant;
+            let c =
+                (x0 * (y1 * s2 - y2 * s1) + x1 * (y2 * s0 - y0 * s2) + x2 * (y0 * s1 - y1 * s0))
+                    / determinant;
+            (a, b, c)
+        };
+        let (a, b, c) = solve(screen.map(|(x, _)| x as f32));
+        let (d, e, f) = solve(screen.map(|(_, y)| y as f32));
+        Some(Self { a, b, c, d, e, f })
+    }
+
+    /// Portrait screen position of a raw reading
+    pub fn apply(&self, raw_x: u16, raw_y: u16) -> (f32, f32) {
+        let (x, y) = (raw_x as f32, raw_y as f32);
+        (
+            self.a * x + self.b * y + self.c,
+            self.d * x + self.e * y + self.f,
+        )
+    }
+}
+
+impl Default for Calibration {
+    /// Typical range of a 3.5" 320x480 panel's resistive layer
+    fn default() -> Self {
+        Self::linear((200, 200), (3900, 3900), 320, 480)
+    }
+}
@@ -0,0 +1,29 @@
+use crate::tft_display::geometry::Point;
+
+/// Touch input in screen coordinates of the display's current orientation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TouchEvent {
+    /// Finger put down
+    Down(Point),
+    /// Finger moved while down
+    Move(Point),
+    /// Finger lifted, at the last position seen
+    Up(Point),
+}
+
+impl TouchEvent {
+    pub fn point(&self) -> Point {
+        match *self {
+            Self::Down(point) | Self::Move(point) | Self::Up(point) => point,
+        }
+    }
+
+    /// The same event at `point`, e.g. translated into a widget's coordinates
+    pub fn with_point(&self, point: Point) -> Self {
+        match self {
+            Self::Down(_) => Self::Down(point),
+            Self::Move(_) => Self::Move(point),
+            Self::Up(_) => Self::Up(point),
+        }
+    }
+}
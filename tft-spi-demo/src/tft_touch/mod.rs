@@ -1 +1,4 @@
+pub mod calibration;
+pub mod event;
+#[allow(clippy::module_inception)]
 pub mod tft_touch;
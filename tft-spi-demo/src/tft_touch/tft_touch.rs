@@ -1,14 +1,162 @@
 // use crate::tft_spi::TftSpi;
-use crate::tft_spi::TftSpiImpl;
+use crate::tft_display::{
+    enums::Rotate, geometry::Point, orientation::Orientation, tft_display::Result,
+    tft_display::TftDisplay,
+};
+use crate::tft_spi::{TftSpi, TftSpiImpl};
+use crate::tft_touch::{calibration::Calibration, event::TouchEvent};
 
+/// XPT2046 control bytes: start bit, channel, 12-bit differential mode, ADC kept on
+const READ_X: u8 = 0xD1;
+const READ_Y: u8 = 0x91;
+const READ_Z1: u8 = 0xB1;
+const READ_Z2: u8 = 0xC1;
+/// Any conversion with both power down bits clear, re-enables the pen interrupt
+const POWER_DOWN: u8 = 0xD0;
+
+/// Readings per axis, the median is used
+const SAMPLES: usize = 5;
+/// Pressure at which the panel counts as touched
+const PRESSURE_THRESHOLD: u16 = 400;
+/// Readings that may be spread over more raw units are discarded as noise
+const MAX_SPREAD: u16 = 120;
+/// Movement below this many pixels isn't reported, it's mostly jitter
+const MOVE_THRESHOLD: i32 = 2;
+/// Polls without pressure before a touch ends, bridges short dropouts
+const RELEASE_POLLS: u8 = 2;
+
+/// XPT2046 resistive touch controller sharing the display's SPI bus
+///
+/// Readings are calibrated to portrait coordinates, then rotated and mirrored like the
+/// display, see `sync`.
 pub struct TftTouch {
     // tft_spi: Box<dyn TftSpi>,
-    _tft_spi: TftSpiImpl,
+    tft_spi: TftSpiImpl,
+    calibration: Calibration,
+    orientation: Orientation,
+    /// Screen size in the portrait orientation
+    portrait: (u16, u16),
+
+    /// Last position while touched
+    touch: Option<Point>,
+    /// Polls without pressure since the last touch
+    released: u8,
 }
 
 impl TftTouch {
     // pub(crate) fn new(tft_spi: Box<dyn TftSpi>) -> Self {
-    pub(crate) fn _new(tft_spi: TftSpiImpl) -> Self {
-        Self { _tft_spi: tft_spi }
+    pub fn new(tft_spi: TftSpiImpl) -> Self {
+        Self {
+            tft_spi,
+            calibration: Calibration::default(),
+            orientation: Orientation::default(),
+            portrait: (320, 480),
+            touch: None,
+            released: 0,
+        }
+    }
+
+    pub fn set_calibration(&mut self, calibration: Calibration) {
+        self.calibration = calibration;
+    }
+
+    pub fn calibration(&self) -> Calibration {
+        self.calibration
+    }
+
+    /// Follows the display's orientation and size, call again after `set_rotation`
+    pub fn sync(&mut self, display: &TftDisplay) {
+        self.orientation = display.orientation();
+        self.portrait = if self.orientation.is_landscape() {
+            (display.height(), display.width())
+        } else {
+            (display.width(), display.height())
+        };
+    }
+
+    /// Raw 12-bit X/Y reading, `None` while not pressed or too noisy
+    pub fn read_raw(&mut self) -> Result<Option<(u16, u16)>> {
+        let z1 = self.tft_spi.read_touch(READ_Z1)?;
+        let z2 = self.tft_spi.read_touch(READ_Z2)?;
+        let pressure = (z1 + 4095).saturating_sub(z2);
+        if z1 == 0 || pressure < PRESSURE_THRESHOLD {
+            self.tft_spi.read_touch(POWER_DOWN)?;
+            return Ok(None);
+        }
+
+        let x = self.sample(READ_X)?;
+        let y = self.sample(READ_Y)?;
+        self.tft_spi.read_touch(POWER_DOWN)?;
+        Ok(x.zip(y))
+    }
+
+    /// Touch position in screen coordinates, `None` while not pressed
+    pub fn read(&mut self) -> Result<Option<Point>> {
+        let Some((raw_x, raw_y)) = self.read_raw()? else {
+            return Ok(None);
+        };
+        let (x, y) = self.calibration.apply(raw_x, raw_y);
+        Ok(Some(self.to_screen(x.round() as i32, y.round() as i32)))
+    }
+
+    /// Reads the panel and reports what changed since the last poll
+    pub fn poll(&mut self) -> Result<Option<TouchEvent>> {
+        let Some(point) = self.read()? else {
+            let Some(last) = self.touch else {
+                return Ok(None);
+            };
+            self.released += 1;
+            if self.released < RELEASE_POLLS {
+                return Ok(None);
+            }
+            self.touch = None;
+            return Ok(Some(TouchEvent::Up(last)));
+        };
+
+        self.released = 0;
+        let event = match self.touch {
+            None => TouchEvent::Down(point),
+            Some(last) if last.distance_squared(point) >= MOVE_THRESHOLD.pow(2) => {
+                TouchEvent::Move(point)
+            }
+            Some(_) => return Ok(None),
+        };
+        self.touch = Some(point);
+        Ok(Some(event))
+    }
+
+    /// Median of a few conversions, `None` if they are spread too far
+    fn sample(&mut self, command: u8) -> Result<Option<u16>> {
+        // The first conversion after switching channels is still settling
+        self.tft_spi.read_touch(command)?;
+
+        let mut samples = [0; SAMPLES];
+        for sample in samples.iter_mut() {
+            *sample = self.tft_spi.read_touch(command)?;
+        }
+        samples.sort_unstable();
+
+        let spread = samples[SAMPLES - 2] - samples[1];
+        Ok((spread <= MAX_SPREAD).then_some(samples[SAMPLES / 2]))
+    }
+
+    /// Portrait position to the display's current orientation
+    fn to_screen(&self, x: i32, y: i32) -> Point {
+        let (w, h) = (self.portrait.0 as i32, self.portrait.1 as i32);
+        let (x, y) = match self.orientation.rotation {
+            Rotate::Degrees0 => (x, y),
+            Rotate::Degrees90 => (y, w - 1 - x),
+            Rotate::Degrees180 => (w - 1 - x, h - 1 - y),
+            Rotate::Degrees270 => (h - 1 - y, x),
+        };
+
+        let (width, height) = if self.orientation.is_landscape() {
+            (h, w)
+        } else {
+            (w, h)
+        };
+        let x = if self.orientation.mirror_x { width - 1 - x } else { x };
+        let y = if self.orientation.mirror_y { height - 1 - y } else { y };
+        Point::new(x.clamp(0, width - 1), y.clamp(0, height - 1))
     }
 }
@@ -0,0 +1,209 @@
+use std::f32::consts::PI;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::tft_display::{
+    color::Color,
+    geometry::{Point, Rect},
+};
+
+/// How quickly springs settle, higher stops sooner
+const SPRING_DAMPING: f32 = 6.0;
+/// Swings of a spring, chosen so it ends exactly at the target
+const SPRING_FREQUENCY: f32 = 4.5 * PI;
+
+/// Shape of a tween's progress over time
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    /// Starts slowly, cubic
+    EaseIn,
+    /// Slows down towards the end, cubic
+    EaseOut,
+    /// Starts and ends slowly
+    #[default]
+    EaseInOut,
+    /// Drops to the end and bounces back a few times
+    Bounce,
+    /// Overshoots and swings around the end until it settles
+    Spring,
+}
+
+impl Easing {
+    /// Progress at time `t`, both from 0 to 1, bounce and spring may leave that range
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t.powi(3),
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut if t < 0.5 => 4.0 * t.powi(3),
+            Easing::EaseInOut => 1.0 - (2.0 - 2.0 * t).powi(3) / 2.0,
+            Easing::Bounce => bounce(t),
+            Easing::Spring => 1.0 - (-SPRING_DAMPING * t).exp() * (SPRING_FREQUENCY * t).cos(),
+        }
+    }
+}
+
+/// Falling to 1 with bounces of a quarter, a sixteenth and a sixty-fourth
+fn bounce(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+/// Values a tween can move between
+pub trait Interpolate: Copy {
+    /// `self` at 0, `to` at 1, beyond that for overshooting easings
+    fn interpolate(self, to: Self, t: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(self, to: Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Interpolate for i32 {
+    fn interpolate(self, to: Self, t: f32) -> Self {
+        self + ((to - self) as f32 * t).round() as i32
+    }
+}
+
+/// Colors can't overshoot, they stop at either end
+impl Interpolate for Color {
+    fn interpolate(self, to: Self, t: f32) -> Self {
+        self.lerp(to, t)
+    }
+}
+
+impl Interpolate for Point {
+    fn interpolate(self, to: Self, t: f32) -> Self {
+        Point::new(self.x.interpolate(to.x, t), self.y.interpolate(to.y, t))
+    }
+}
+
+impl Interpolate for Rect {
+    fn interpolate(self, to: Self, t: f32) -> Self {
+        let size = |from: u16, to: u16| (from as i32).interpolate(to as i32, t).max(0) as u16;
+        Rect::new(
+            self.x.interpolate(to.x, t),
+            self.y.interpolate(to.y, t),
+            size(self.w, to.w),
+            size(self.h, to.h),
+        )
+    }
+}
+
+/// Value moving from one end to the other over time, starting when created
+#[derive(Clone, Copy, Debug)]
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    start: Instant,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl<T: Interpolate> Tween<T> {
+    pub fn new(from: T, to: T, duration: Duration) -> Self {
+        Self {
+            from,
+            to,
+            start: Instant::now(),
+            duration,
+            easing: Easing::default(),
+        }
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Holds the start value for `delay` first, e.g. to stagger several tweens
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.start += delay;
+        self
+    }
+
+    pub fn from(&self) -> T {
+        self.from
+    }
+
+    pub fn to(&self) -> T {
+        self.to
+    }
+
+    /// Progress at `now`, from 0 to 1
+    pub fn progress(&self, now: Instant) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        let elapsed = now.saturating_duration_since(self.start);
+        (elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    pub fn value_at(&self, now: Instant) -> T {
+        match self.progress(now) {
+            t if t >= 1.0 => self.to,
+            t => self.from.interpolate(self.to, self.easing.apply(t)),
+        }
+    }
+
+    pub fn is_done(&self, now: Instant) -> bool {
+        self.progress(now) >= 1.0
+    }
+}
+
+/// Paces a render loop to a target frame rate
+///
+/// Changes made between frames pile up as dirty areas and go out together in the
+/// next frame's flush. Frames that are late are not caught up.
+#[derive(Clone, Copy, Debug)]
+pub struct FrameClock {
+    interval: Duration,
+    next: Instant,
+}
+
+impl FrameClock {
+    pub fn new(fps: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / fps.max(1),
+            next: Instant::now(),
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Whether the next frame is due, `true` at most once per interval
+    pub fn tick(&mut self) -> bool {
+        let now = Instant::now();
+        if now < self.next {
+            return false;
+        }
+        self.next = (self.next + self.interval).max(now);
+        true
+    }
+
+    /// Sleeps until the next frame is due, at most `max`, e.g. to keep polling input
+    pub fn wait(&self, max: Duration) {
+        let left = self.next.saturating_duration_since(Instant::now());
+        if !left.is_zero() {
+            thread::sleep(left.min(max));
+        }
+    }
+}
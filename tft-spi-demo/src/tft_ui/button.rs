@@ -0,0 +1,85 @@
+use crate::tft_display::{
+    canvas::Canvas, color::Color, enums::Align, geometry::Rect, tft_display::Result,
+};
+use crate::tft_touch::event::TouchEvent;
+use crate::tft_ui::{
+    style::Style,
+    widget::{self, Widget, WidgetEvent, WidgetState},
+};
+
+/// Push button with a text label, reports `Clicked`
+pub struct Button {
+    state: WidgetState,
+    text: String,
+    /// Face and text colors instead of the style's
+    face: Option<Color>,
+    text_color: Option<Color>,
+}
+
+impl Button {
+    pub fn new(rect: Rect, text: impl Into<String>) -> Self {
+        Self {
+            state: WidgetState::new(rect),
+            text: text.into(),
+            face: None,
+            text_color: None,
+        }
+    }
+
+    /// Own face and text colors, e.g. for color coded actions
+    pub fn with_colors(mut self, face: Color, text: Color) -> Self {
+        self.face = Some(face);
+        self.text_color = Some(text);
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        if self.text != text {
+            self.text = text;
+            self.state.dirty = true;
+        }
+    }
+}
+
+impl Widget for Button {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let (face, text) = if !self.state.enabled {
+            (style.surface, style.disabled)
+        } else if self.state.pressed {
+            (style.pressed, self.text_color.unwrap_or(style.foreground))
+        } else {
+            (
+                self.face.unwrap_or(style.surface),
+                self.text_color.unwrap_or(style.foreground),
+            )
+        };
+
+        let rect = self.state.rect;
+        canvas.fill_round_rect(rect, style.corner_radius, face)?;
+        let content = rect.inset(style.padding);
+        widget::draw_text_block(canvas, style, &self.text, content, Align::Center, text)
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn touch(&mut self, event: TouchEvent) -> Option<WidgetEvent> {
+        self.state
+            .track_press(event)
+            .then_some(WidgetEvent::Clicked)
+    }
+}
@@ -0,0 +1,637 @@
+use std::collections::VecDeque;
+
+use crate::tft_display::{
+    canvas::Canvas, color::Color, enums::Align, geometry::Rect, tft_display::Result,
+};
+use crate::tft_ui::{
+    style::Style,
+    widget::{Widget, WidgetState},
+};
+
+/// Samples kept unless changed with `with_capacity`
+const CAPACITY: usize = 512;
+/// Intervals between the labeled values of an autoscaled axis, roughly
+const TICKS: f32 = 4.0;
+/// Room above and below the plot for the top and bottom labels
+const LABEL_MARGIN: u16 = 8;
+/// Width of the labels left of the plot unless changed with `with_axis_width`
+const AXIS_WIDTH: u16 = 48;
+
+/// Samples of a chart, the newest is drawn at the right edge
+struct Series {
+    values: VecDeque<f32>,
+    capacity: usize,
+}
+
+impl Series {
+    fn new() -> Self {
+        Self {
+            values: VecDeque::new(),
+            capacity: CAPACITY,
+        }
+    }
+
+    fn push(&mut self, value: f32) {
+        self.values.push_back(value);
+        self.trim();
+    }
+
+    fn set_values(&mut self, values: impl IntoIterator<Item = f32>) {
+        self.values = values.into_iter().collect();
+        self.trim();
+    }
+
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        let excess = self.values.len().saturating_sub(self.capacity);
+        self.values.drain(..excess);
+    }
+
+    /// Up to `count` values, newest first, with how many steps they are left of the edge
+    fn newest(&self, count: usize) -> impl Iterator<Item = (usize, f32)> + '_ {
+        self.values.iter().rev().copied().take(count).enumerate()
+    }
+
+    /// Smallest and largest of the newest `count` values, skipping gaps
+    fn bounds(&self, count: usize) -> Option<(f32, f32)> {
+        self.newest(count)
+            .map(|(_, value)| value)
+            .filter(|value| value.is_finite())
+            .fold(None, |bounds, value| match bounds {
+                Some((min, max)) => Some((value.min(min), value.max(max))),
+                None => Some((value, value)),
+            })
+    }
+}
+
+/// Value range of a chart mapped onto its plot
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Scale {
+    min: f32,
+    max: f32,
+    /// Distance between labeled values
+    tick: f32,
+}
+
+impl Scale {
+    /// `min` to `max` labeled at round steps, e.g. 0, 5, 10
+    fn fixed(min: f32, max: f32) -> Self {
+        let (min, max) = if max > min {
+            (min, max)
+        } else {
+            (min - 1.0, min + 1.0)
+        };
+        Self {
+            min,
+            max,
+            tick: nice_step((max - min) / TICKS),
+        }
+    }
+
+    /// The smallest range of whole ticks around `min` to `max`, so it changes rarely
+    fn around(min: f32, max: f32) -> Self {
+        let Scale { min, max, tick } = Self::fixed(min, max);
+        Self {
+            min: (min / tick).floor() * tick,
+            max: (max / tick).ceil() * tick,
+            tick,
+        }
+    }
+
+    /// Row of `value` in `plot`, not clamped
+    fn y(&self, value: f32, plot: Rect) -> f32 {
+        let fraction = (value - self.min) / (self.max - self.min);
+        (plot.bottom() - 1) as f32 - fraction * (plot.h.saturating_sub(1)) as f32
+    }
+
+    /// Labeled values within the range, from the bottom
+    fn ticks(&self) -> impl Iterator<Item = f32> + '_ {
+        let first = (self.min / self.tick).ceil() as i64;
+        let last = (self.max / self.tick).floor() as i64;
+        (first..=last).map(|index| index as f32 * self.tick)
+    }
+
+    fn label(&self, value: f32, unit: &str) -> String {
+        let decimals = (-self.tick.log10().floor()).max(0.0) as usize;
+        format!("{value:.decimals$}{unit}")
+    }
+}
+
+/// 1, 2 or 5 times a power of ten, at least `step`
+fn nice_step(step: f32) -> f32 {
+    let power = 10f32.powf(step.log10().floor());
+    let nice = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .find(|&nice| nice * power >= step)
+        .unwrap_or(10.0);
+    nice * power
+}
+
+/// Value axis left of a plot with gridlines at the labeled values
+struct Axes {
+    width: u16,
+    /// Appended to the labels, e.g. "C" or "kW"
+    unit: String,
+    /// Fixed range instead of autoscaling
+    range: Option<(f32, f32)>,
+}
+
+impl Axes {
+    fn new() -> Self {
+        Self {
+            width: AXIS_WIDTH,
+            unit: String::new(),
+            range: None,
+        }
+    }
+
+    /// Part of `rect` right of the labels, with room for the top and bottom one
+    fn plot(&self, rect: Rect) -> Rect {
+        let width = self.width.min(rect.w);
+        let margin = LABEL_MARGIN.min(rect.h / 2);
+        Rect::new(
+            rect.x + width as i32,
+            rect.y + margin as i32,
+            rect.w - width,
+            rect.h - 2 * margin,
+        )
+    }
+
+    fn draw(
+        &self,
+        canvas: &mut dyn Canvas,
+        style: &Style,
+        rect: Rect,
+        scale: &Scale,
+    ) -> Result<()> {
+        let plot = self.plot(rect);
+        for tick in scale.ticks() {
+            let y = scale.y(tick, plot).round() as i32;
+            canvas.fill_rect(Rect::new(plot.x, y, plot.w, 1), style.surface)?;
+        }
+        let axis = Rect::new(plot.x - 1, plot.y, 1, plot.h + 1);
+        canvas.fill_rect(axis, style.disabled)?;
+        canvas.fill_rect(Rect::new(plot.x, plot.bottom(), plot.w, 1), style.disabled)?;
+
+        // Labels aren't touched when only the plot is drawn again
+        let labels = Rect::new(rect.x, rect.y, self.width.min(rect.w), rect.h);
+        if canvas.clip().intersect(labels).is_none() {
+            return Ok(());
+        }
+        let font = style.font.as_ref();
+        let height = font.line_metrics().line_height;
+        for tick in scale.ticks() {
+            let y = scale.y(tick, plot).round() as i32 - height / 2;
+            let bounds = Rect::new(rect.x, y, labels.w.saturating_sub(4), height as u16);
+            let label = scale.label(tick, &self.unit);
+            canvas.draw_text_box(font, &label, bounds, Align::Right, style.foreground, None)?;
+        }
+        Ok(())
+    }
+}
+
+/// Line through the newest values of `series`, `step` columns apart, gaps skipped
+fn draw_line(
+    canvas: &mut dyn Canvas,
+    series: &Series,
+    plot: Rect,
+    step: u16,
+    scale: &Scale,
+    color: Color,
+) -> Result<()> {
+    let clip = canvas.clip();
+    let x = |index: usize| (plot.right() - 1 - (index * step as usize) as i32) as f32;
+    let points: Vec<_> = series.newest(line_points(plot, step)).collect();
+
+    // Oldest first, so blending where segments join doesn't depend on what is drawn
+    let mut older: Option<(f32, f32)> = None;
+    for &(index, value) in points.iter().rev() {
+        let point = value.is_finite().then(|| (x(index), scale.y(value, plot)));
+        if let (Some((x0, y0)), Some((x1, y1))) = (older, point) {
+            if x1 >= clip.x as f32 - 1.0 && x0 <= clip.right() as f32 {
+                canvas.draw_line_aa(x0, y0, x1, y1, color)?;
+            }
+        }
+        older = point;
+    }
+    Ok(())
+}
+
+/// Points of a line that reach into `plot`, including one left of it
+fn line_points(plot: Rect, step: u16) -> usize {
+    plot.w as usize / step as usize + 2
+}
+
+/// Columns where the newest segment of a line meets the previous one
+///
+/// Antialiasing of steep segments reaches one column past their end.
+fn line_join(plot: Rect, step: u16) -> Option<Rect> {
+    let x = plot.right() - 2 - step as i32;
+    Rect::new(x, plot.y, 2, plot.h).intersect(plot)
+}
+
+/// Line chart of a time series with a labeled value axis, autoscaled by default
+///
+/// `push` appends a value at the right edge and moves the line left, `Ui` then moves
+/// the drawn pixels and draws only the new columns unless the scale changed.
+pub struct LineChart {
+    state: WidgetState,
+    series: Series,
+    axes: Axes,
+    /// Columns between values
+    step: u16,
+    /// Line color instead of the style's accent
+    color: Option<Color>,
+    /// Columns the plot moved left since `take_shift`
+    shift: i32,
+}
+
+impl LineChart {
+    pub fn new(rect: Rect) -> Self {
+        Self {
+            state: WidgetState::new(rect),
+            series: Series::new(),
+            axes: Axes::new(),
+            step: 4,
+            color: None,
+            shift: 0,
+        }
+    }
+
+    pub fn with_step(mut self, step: u16) -> Self {
+        self.step = step.max(1);
+        self
+    }
+
+    /// Shows `min` to `max` instead of autoscaling
+    pub fn with_range(mut self, min: f32, max: f32) -> Self {
+        self.axes.range = Some((min, max));
+        self
+    }
+
+    pub fn with_unit(mut self, unit: impl Into<String>) -> Self {
+        self.axes.unit = unit.into();
+        self
+    }
+
+    pub fn with_axis_width(mut self, width: u16) -> Self {
+        self.axes.width = width;
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Most values kept, older ones are dropped
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.series.set_capacity(capacity);
+        self
+    }
+
+    pub fn values(&self) -> &VecDeque<f32> {
+        &self.series.values
+    }
+
+    /// Appends a value, NaN leaves a gap in the line
+    pub fn push(&mut self, value: f32) {
+        let scale = self.scale();
+        self.series.push(value);
+        if self.scale() == scale {
+            self.shift += self.step as i32;
+        } else {
+            self.state.dirty = true;
+        }
+    }
+
+    pub fn set_values(&mut self, values: impl IntoIterator<Item = f32>) {
+        self.series.set_values(values);
+        self.state.dirty = true;
+    }
+
+    pub fn clear(&mut self) {
+        self.set_values([]);
+    }
+
+    fn plot(&self) -> Rect {
+        self.axes.plot(self.state.rect)
+    }
+
+    fn scale(&self) -> Scale {
+        if let Some((min, max)) = self.axes.range {
+            return Scale::fixed(min, max);
+        }
+        let count = line_points(self.plot(), self.step);
+        let (min, max) = self.series.bounds(count).unwrap_or((0.0, 1.0));
+        Scale::around(min, max)
+    }
+}
+
+impl Widget for LineChart {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let scale = self.scale();
+        self.axes.draw(canvas, style, self.state.rect, &scale)?;
+
+        let color = if self.state.enabled {
+            self.color.unwrap_or(style.accent)
+        } else {
+            style.disabled
+        };
+        let plot = self.plot();
+        canvas.push_clip(plot);
+        let result = draw_line(canvas, &self.series, plot, self.step, &scale, color);
+        canvas.pop_clip();
+        result
+    }
+
+    fn take_shift(&mut self) -> Option<(Rect, i32)> {
+        let shift = std::mem::take(&mut self.shift);
+        (shift != 0).then(|| (self.plot(), shift))
+    }
+
+    fn fixed_rect(&self) -> Option<Rect> {
+        line_join(self.plot(), self.step)
+    }
+}
+
+/// Bar chart of a time series with a labeled value axis, bars grow from zero
+///
+/// Appends like `LineChart`, each value adds one bar at the right edge.
+pub struct BarChart {
+    state: WidgetState,
+    series: Series,
+    axes: Axes,
+    bar_width: u16,
+    gap: u16,
+    /// Bar color instead of the style's accent
+    color: Option<Color>,
+    /// Columns the plot moved left since `take_shift`
+    shift: i32,
+}
+
+impl BarChart {
+    pub fn new(rect: Rect) -> Self {
+        Self {
+            state: WidgetState::new(rect),
+            series: Series::new(),
+            axes: Axes::new(),
+            bar_width: 8,
+            gap: 2,
+            color: None,
+            shift: 0,
+        }
+    }
+
+    /// Width of the bars and the space between them
+    pub fn with_bars(mut self, width: u16, gap: u16) -> Self {
+        self.bar_width = width.max(1);
+        self.gap = gap;
+        self
+    }
+
+    /// Shows `min` to `max` instead of autoscaling
+    pub fn with_range(mut self, min: f32, max: f32) -> Self {
+        self.axes.range = Some((min, max));
+        self
+    }
+
+    pub fn with_unit(mut self, unit: impl Into<String>) -> Self {
+        self.axes.unit = unit.into();
+        self
+    }
+
+    pub fn with_axis_width(mut self, width: u16) -> Self {
+        self.axes.width = width;
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Most values kept, older ones are dropped
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.series.set_capacity(capacity);
+        self
+    }
+
+    pub fn values(&self) -> &VecDeque<f32> {
+        &self.series.values
+    }
+
+    /// Appends a bar, NaN leaves an empty slot
+    pub fn push(&mut self, value: f32) {
+        let scale = self.scale();
+        self.series.push(value);
+        if self.scale() == scale {
+            self.shift += self.step() as i32;
+        } else {
+            self.state.dirty = true;
+        }
+    }
+
+    pub fn set_values(&mut self, values: impl IntoIterator<Item = f32>) {
+        self.series.set_values(values);
+        self.state.dirty = true;
+    }
+
+    pub fn clear(&mut self) {
+        self.set_values([]);
+    }
+
+    fn step(&self) -> u16 {
+        self.bar_width + self.gap
+    }
+
+    /// Bars that reach into the plot, including a partial one at the left
+    fn count(&self) -> usize {
+        (self.plot().w / self.step()) as usize + 1
+    }
+
+    fn plot(&self) -> Rect {
+        self.axes.plot(self.state.rect)
+    }
+
+    fn scale(&self) -> Scale {
+        if let Some((min, max)) = self.axes.range {
+            return Scale::fixed(min, max);
+        }
+        let (min, max) = self.series.bounds(self.count()).unwrap_or((0.0, 1.0));
+        Scale::around(min.min(0.0), max.max(0.0))
+    }
+
+    fn draw_bars(&self, canvas: &mut dyn Canvas, scale: &Scale, color: Color) -> Result<()> {
+        let plot = self.plot();
+        let base = scale.y(0f32.clamp(scale.min, scale.max), plot).round() as i32;
+        let clip = canvas.clip();
+        let step = self.step() as i32;
+
+        for (index, value) in self.series.newest(self.count()) {
+            let right = plot.right() - index as i32 * step;
+            if !value.is_finite() || right <= clip.x || right - step >= clip.right() {
+                continue;
+            }
+            let top = scale.y(value, plot).round() as i32;
+            let (top, bottom) = (top.min(base), top.max(base) + 1);
+            let x = right - self.bar_width as i32;
+            canvas.fill_rect(
+                Rect::new(x, top, self.bar_width, (bottom - top) as u16),
+                color,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Widget for BarChart {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let scale = self.scale();
+        self.axes.draw(canvas, style, self.state.rect, &scale)?;
+
+        let color = if self.state.enabled {
+            self.color.unwrap_or(style.accent)
+        } else {
+            style.disabled
+        };
+        canvas.push_clip(self.plot());
+        let result = self.draw_bars(canvas, &scale, color);
+        canvas.pop_clip();
+        result
+    }
+
+    fn take_shift(&mut self) -> Option<(Rect, i32)> {
+        let shift = std::mem::take(&mut self.shift);
+        (shift != 0).then(|| (self.plot(), shift))
+    }
+}
+
+/// Small line chart without axes, scaled to the values shown, e.g. next to a label
+///
+/// Appends like `LineChart`, though the scale changes more often.
+pub struct Sparkline {
+    state: WidgetState,
+    series: Series,
+    /// Columns between values
+    step: u16,
+    /// Line color instead of the style's accent
+    color: Option<Color>,
+    /// Columns the line moved left since `take_shift`
+    shift: i32,
+}
+
+impl Sparkline {
+    pub fn new(rect: Rect) -> Self {
+        Self {
+            state: WidgetState::new(rect),
+            series: Series::new(),
+            step: 2,
+            color: None,
+            shift: 0,
+        }
+    }
+
+    pub fn with_step(mut self, step: u16) -> Self {
+        self.step = step.max(1);
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Most values kept, older ones are dropped
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.series.set_capacity(capacity);
+        self
+    }
+
+    pub fn values(&self) -> &VecDeque<f32> {
+        &self.series.values
+    }
+
+    /// Appends a value, NaN leaves a gap in the line
+    pub fn push(&mut self, value: f32) {
+        let scale = self.scale();
+        self.series.push(value);
+        if self.scale() == scale {
+            self.shift += self.step as i32;
+        } else {
+            self.state.dirty = true;
+        }
+    }
+
+    pub fn set_values(&mut self, values: impl IntoIterator<Item = f32>) {
+        self.series.set_values(values);
+        self.state.dirty = true;
+    }
+
+    pub fn clear(&mut self) {
+        self.set_values([]);
+    }
+
+    /// Inside the rectangle, so the line's antialiasing isn't cut off
+    fn plot(&self) -> Rect {
+        self.state.rect.inset(1)
+    }
+
+    fn scale(&self) -> Scale {
+        let count = line_points(self.plot(), self.step);
+        let (min, max) = self.series.bounds(count).unwrap_or((0.0, 1.0));
+        Scale::fixed(min, max)
+    }
+}
+
+impl Widget for Sparkline {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let color = if self.state.enabled {
+            self.color.unwrap_or(style.accent)
+        } else {
+            style.disabled
+        };
+        let plot = self.plot();
+        canvas.push_clip(plot);
+        let result = draw_line(canvas, &self.series, plot, self.step, &self.scale(), color);
+        canvas.pop_clip();
+        result
+    }
+
+    fn take_shift(&mut self) -> Option<(Rect, i32)> {
+        let shift = std::mem::take(&mut self.shift);
+        (shift != 0).then(|| (self.plot(), shift))
+    }
+
+    fn fixed_rect(&self) -> Option<Rect> {
+        line_join(self.plot(), self.step)
+    }
+}
@@ -0,0 +1,103 @@
+use crate::tft_display::{canvas::Canvas, enums::Align, geometry::Rect, tft_display::Result};
+use crate::tft_touch::event::TouchEvent;
+use crate::tft_ui::{
+    style::Style,
+    widget::{self, Widget, WidgetEvent, WidgetState},
+};
+
+/// Box with a check mark and a label to its right, reports `Toggled`
+///
+/// The whole rectangle, label included, takes touch.
+pub struct Checkbox {
+    state: WidgetState,
+    checked: bool,
+    text: String,
+}
+
+impl Checkbox {
+    pub fn new(rect: Rect, text: impl Into<String>, checked: bool) -> Self {
+        Self {
+            state: WidgetState::new(rect),
+            checked,
+            text: text.into(),
+        }
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    pub fn set_checked(&mut self, checked: bool) {
+        if self.checked != checked {
+            self.checked = checked;
+            self.state.dirty = true;
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl Widget for Checkbox {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let rect = self.state.rect;
+        let size = rect.h.min(rect.w);
+        let top = rect.y + (rect.h - size) as i32 / 2;
+        let check_box = Rect::new(rect.x, top, size, size);
+
+        let (face, text) = match (self.state.enabled, self.checked) {
+            (false, _) => (style.surface, style.disabled),
+            (true, _) if self.state.pressed => (style.pressed, style.foreground),
+            (true, true) => (style.accent, style.foreground),
+            (true, false) => (style.surface, style.foreground),
+        };
+        canvas.fill_round_rect(check_box, style.corner_radius / 2, face)?;
+
+        if self.checked {
+            // Check mark through three points, doubled for a two pixel stroke
+            let (x, y, s) = (check_box.x as f32, check_box.y as f32, size as f32);
+            let points = [
+                (x + s * 0.25, y + s * 0.5),
+                (x + s * 0.42, y + s * 0.68),
+                (x + s * 0.75, y + s * 0.3),
+            ];
+            for offset in [0.0, 1.0] {
+                for pair in points.windows(2) {
+                    let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+                    canvas.draw_line_aa(x0, y0 + offset, x1, y1 + offset, text)?;
+                }
+            }
+        }
+
+        let gap = style.padding as i32;
+        let label_x = check_box.right() + gap;
+        let label = Rect::new(
+            label_x,
+            rect.y,
+            (rect.right() - label_x).max(0) as u16,
+            rect.h,
+        );
+        widget::draw_text_block(canvas, style, &self.text, label, Align::Left, text)
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn touch(&mut self, event: TouchEvent) -> Option<WidgetEvent> {
+        if !self.state.track_press(event) {
+            return None;
+        }
+        self.set_checked(!self.checked);
+        Some(WidgetEvent::Toggled(self.checked))
+    }
+}
@@ -0,0 +1,175 @@
+use crate::tft_display::{canvas::Canvas, enums::Align, geometry::Rect, tft_display::Result};
+use crate::tft_touch::event::TouchEvent;
+use crate::tft_ui::{
+    style::Style,
+    widget::{self, Widget, WidgetEvent, WidgetState},
+};
+
+/// Most buttons a `Dialog` shows
+pub const MAX_BUTTONS: usize = 3;
+const BUTTON_HEIGHT: u16 = 40;
+/// Space around the content and between the buttons
+const MARGIN: u16 = 8;
+
+/// Box with a title, a message and up to three buttons, reports `Selected`
+///
+/// Hidden until opened with `Ui::open_dialog`, which shows it above everything else
+/// and keeps touch away from the widgets underneath. Pressing a button closes it and
+/// reports the button's index.
+pub struct Dialog {
+    state: WidgetState,
+    title: String,
+    message: String,
+    buttons: Vec<String>,
+    /// Button under the finger while pressed
+    pressed: Option<usize>,
+}
+
+impl Dialog {
+    pub fn new(rect: Rect, title: impl Into<String>, message: impl Into<String>) -> Self {
+        let mut state = WidgetState::new(rect);
+        state.visible = false;
+        Self {
+            state,
+            title: title.into(),
+            message: message.into(),
+            buttons: vec!["OK".to_string()],
+            pressed: None,
+        }
+    }
+
+    /// Buttons from left to right, the last is the default action and highlighted
+    ///
+    /// Only the first `MAX_BUTTONS` are kept.
+    pub fn with_buttons<I, S>(mut self, buttons: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.buttons = buttons
+            .into_iter()
+            .take(MAX_BUTTONS)
+            .map(Into::into)
+            .collect();
+        self
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        let title = title.into();
+        if self.title != title {
+            self.title = title;
+            self.state.dirty = true;
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn set_message(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        if self.message != message {
+            self.message = message;
+            self.state.dirty = true;
+        }
+    }
+
+    pub fn buttons(&self) -> &[String] {
+        &self.buttons
+    }
+
+    fn button_rect(&self, index: usize) -> Rect {
+        let content = self.state.rect.inset(MARGIN);
+        let count = self.buttons.len().max(1) as i32;
+        let gap = MARGIN as i32;
+        let width = (content.w as i32 - gap * (count - 1)).max(0);
+        let from = width * index as i32 / count;
+        let to = width * (index as i32 + 1) / count;
+        let height = BUTTON_HEIGHT.min(content.h);
+        let x = content.x + from + gap * index as i32;
+        let y = content.bottom() - height as i32;
+        Rect::new(x, y, (to - from) as u16, height)
+    }
+
+    fn button_at(&self, event: TouchEvent) -> Option<usize> {
+        let point = event.point();
+        (0..self.buttons.len()).find(|&index| self.button_rect(index).contains_point(point))
+    }
+}
+
+impl Widget for Dialog {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let rect = self.state.rect;
+        let radius = style.corner_radius;
+        canvas.fill_round_rect(rect, radius, style.disabled)?;
+        canvas.fill_round_rect(rect.inset(1), radius.saturating_sub(1), style.surface)?;
+
+        let content = rect.inset(MARGIN);
+        let line_height = style.font.line_metrics().line_height;
+        let title = Rect::new(content.x, content.y, content.w, line_height as u16);
+        widget::draw_text_block(
+            canvas,
+            style,
+            &self.title,
+            title,
+            Align::Center,
+            style.foreground,
+        )?;
+
+        let top = title.bottom() + MARGIN as i32;
+        let bottom = content.bottom() - (BUTTON_HEIGHT + MARGIN) as i32;
+        let height = (bottom - top).max(0) as u16;
+        let message = Rect::new(content.x, top, content.w, height);
+        let color = style.foreground.lerp(style.surface, 0.2);
+        widget::draw_text_block(canvas, style, &self.message, message, Align::Center, color)?;
+
+        let primary = self.buttons.len().saturating_sub(1);
+        for (index, label) in self.buttons.iter().enumerate() {
+            let (face, text) = match (self.pressed == Some(index), index == primary) {
+                (true, _) => (style.pressed, style.foreground),
+                (false, true) => (style.accent, style.background),
+                (false, false) => (style.background, style.foreground),
+            };
+            let button = self.button_rect(index);
+            canvas.fill_round_rect(button, radius, face)?;
+            let label_rect = button.inset(style.padding);
+            widget::draw_text_block(canvas, style, label, label_rect, Align::Center, text)?;
+        }
+        Ok(())
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn touch(&mut self, event: TouchEvent) -> Option<WidgetEvent> {
+        let under = self.button_at(event);
+        let pressed = match event {
+            TouchEvent::Up(_) => None,
+            _ => under,
+        };
+        if self.pressed != pressed {
+            self.pressed = pressed;
+            self.state.dirty = true;
+        }
+
+        let TouchEvent::Up(_) = event else {
+            return None;
+        };
+        let index = under?;
+        self.set_visible(false);
+        Some(WidgetEvent::Selected(index))
+    }
+}
@@ -0,0 +1,616 @@
+use std::time::{Duration, Instant};
+
+use crate::tft_display::{
+    canvas::Canvas, color::Color, enums::Align, geometry::Rect, tft_display::Result,
+};
+use crate::tft_ui::{
+    animation::{Easing, Tween},
+    style::Style,
+    widget::{self, Widget, WidgetState},
+};
+
+/// How long the shown value takes to reach a new one unless changed with
+/// `with_animation`
+const ANIMATION_TIME: Duration = Duration::from_millis(400);
+/// Where the scale of round gauges starts, in degrees clockwise from the right
+const START_ANGLE: f32 = 135.0;
+/// Angle the scale of round gauges spans, open at the bottom
+const SWEEP: f32 = 270.0;
+/// Fraction of zone colors blended into the track behind the value
+const ZONE_FADE: f32 = 0.5;
+
+/// Colored part of a gauge's scale, e.g. the comfortable temperature range
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Zone {
+    pub from: f32,
+    pub to: f32,
+    pub color: Color,
+}
+
+impl Zone {
+    pub fn new(from: f32, to: f32, color: Color) -> Self {
+        Self {
+            from: from.min(to),
+            to: from.max(to),
+            color,
+        }
+    }
+
+    fn contains(&self, value: f32) -> bool {
+        value >= self.from && value <= self.to
+    }
+}
+
+/// Value range, zones and thresholds of a gauge, with the value shown moving
+/// smoothly to the one set
+struct Reading {
+    min: f32,
+    max: f32,
+    /// Value set
+    value: f32,
+    /// Value drawn, differs from `value` while animating
+    shown: f32,
+    tween: Option<Tween<f32>>,
+    duration: Duration,
+    easing: Easing,
+    zones: Vec<Zone>,
+    /// Colors of the value from each threshold up, sorted
+    thresholds: Vec<(f32, Color)>,
+    decimals: usize,
+    unit: String,
+}
+
+impl Reading {
+    fn new(min: f32, max: f32, value: f32) -> Self {
+        let (min, max) = if min <= max { (min, max) } else { (max, min) };
+        let value = value.clamp(min, max);
+        Self {
+            min,
+            max,
+            value,
+            shown: value,
+            tween: None,
+            duration: ANIMATION_TIME,
+            easing: Easing::EaseOut,
+            zones: Vec::new(),
+            thresholds: Vec::new(),
+            decimals: 0,
+            unit: String::new(),
+        }
+    }
+
+    fn add_threshold(&mut self, at: f32, color: Color) {
+        let index = self.thresholds.partition_point(|&(other, _)| other <= at);
+        self.thresholds.insert(index, (at, color));
+    }
+
+    /// Sets the value, `true` if the drawing changes right away
+    fn set_value(&mut self, value: f32) -> bool {
+        let value = value.clamp(self.min, self.max);
+        if self.value == value {
+            return false;
+        }
+        self.value = value;
+        if self.duration.is_zero() {
+            self.shown = value;
+            self.tween = None;
+            return true;
+        }
+        let tween = Tween::new(self.shown, value, self.duration).with_easing(self.easing);
+        self.tween = Some(tween);
+        false
+    }
+
+    /// Moves the shown value towards the one set, `true` if it changed
+    fn animate(&mut self, now: Instant) -> bool {
+        let Some(tween) = self.tween else {
+            return false;
+        };
+        self.shown = tween.value_at(now).clamp(self.min, self.max);
+        if tween.is_done(now) {
+            self.tween = None;
+        }
+        true
+    }
+
+    /// Position of `value` on the scale, 0 to 1
+    fn fraction(&self, value: f32) -> f32 {
+        if self.max > self.min {
+            ((value - self.min) / (self.max - self.min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Color of `value`: from the threshold below it, else the zone it's in
+    fn color(&self, value: f32, default: Color) -> Color {
+        let threshold = self.thresholds.iter().rev().find(|&&(at, _)| value >= at);
+        match threshold {
+            Some(&(_, color)) => color,
+            None => self.zone_color(value).unwrap_or(default),
+        }
+    }
+
+    fn zone_color(&self, value: f32) -> Option<Color> {
+        let zone = self.zones.iter().rev().find(|zone| zone.contains(value));
+        zone.map(|zone| zone.color)
+    }
+
+    fn format(&self, value: f32) -> String {
+        format!("{value:.0$}{1}", self.decimals, self.unit)
+    }
+}
+
+/// Center and outer radius of the largest circle in `rect`
+fn circle(rect: Rect) -> (f32, f32, f32) {
+    let cx = rect.x as f32 + (rect.w as f32 - 1.0) / 2.0;
+    let cy = rect.y as f32 + (rect.h as f32 - 1.0) / 2.0;
+    (cx, cy, (rect.w.min(rect.h) as f32 / 2.0 - 1.0).max(0.0))
+}
+
+/// Angle in degrees of a position on the scale of a round gauge
+fn angle(fraction: f32) -> f32 {
+    START_ANGLE + fraction * SWEEP
+}
+
+/// Draws `text` centered in a line of `rect` whose middle is at `y`
+fn draw_centered(
+    canvas: &mut dyn Canvas,
+    style: &Style,
+    text: &str,
+    rect: Rect,
+    y: f32,
+    color: Color,
+) -> Result<()> {
+    let height = style.font.line_metrics().line_height;
+    let line = Rect::new(rect.x, y.round() as i32 - height / 2, rect.w, height as u16);
+    widget::draw_text_block(canvas, style, text, line, Align::Center, color)
+}
+
+/// Circular arc gauge filling up clockwise, the value in its middle
+pub struct Gauge {
+    state: WidgetState,
+    reading: Reading,
+    /// Shown under the value, e.g. what is measured
+    label: Option<String>,
+    /// Width of the arc, a fifth of the radius unless set
+    thickness: Option<f32>,
+}
+
+impl Gauge {
+    pub fn new(rect: Rect, min: f32, max: f32, value: f32) -> Self {
+        Self {
+            state: WidgetState::new(rect),
+            reading: Reading::new(min, max, value),
+            label: None,
+            thickness: None,
+        }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn with_thickness(mut self, thickness: f32) -> Self {
+        self.thickness = Some(thickness.max(1.0));
+        self
+    }
+
+    pub fn with_zone(mut self, zone: Zone) -> Self {
+        self.reading.zones.push(zone);
+        self
+    }
+
+    /// Draws the value in `color` from `at` up to the next threshold
+    pub fn with_threshold(mut self, at: f32, color: Color) -> Self {
+        self.reading.add_threshold(at, color);
+        self
+    }
+
+    /// Appended to the value, e.g. "C" or "kW"
+    pub fn with_unit(mut self, unit: impl Into<String>) -> Self {
+        self.reading.unit = unit.into();
+        self
+    }
+
+    pub fn with_decimals(mut self, decimals: usize) -> Self {
+        self.reading.decimals = decimals;
+        self
+    }
+
+    /// How long value changes take to show, zero jumps
+    pub fn with_animation(mut self, duration: Duration) -> Self {
+        self.reading.duration = duration;
+        self
+    }
+
+    /// How the shown value moves to a new one, eased out unless changed
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.reading.easing = easing;
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.reading.value
+    }
+
+    pub fn range(&self) -> (f32, f32) {
+        (self.reading.min, self.reading.max)
+    }
+
+    /// Sets the value, clamped to the range, the gauge moves there over time
+    pub fn set_value(&mut self, value: f32) {
+        if self.reading.set_value(value) {
+            self.state.dirty = true;
+        }
+    }
+
+    fn draw_gauge(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let reading = &self.reading;
+        let (cx, cy, outer) = circle(self.state.rect);
+        let thickness = self.thickness.unwrap_or(outer / 5.0).min(outer);
+        let radius = outer - thickness / 2.0;
+
+        let end = angle(1.0);
+        canvas.draw_arc_aa(cx, cy, radius, thickness, START_ANGLE, end, style.surface)?;
+        for zone in &reading.zones {
+            let (from, to) = (
+                angle(reading.fraction(zone.from)),
+                angle(reading.fraction(zone.to)),
+            );
+            let color = style.surface.lerp(zone.color, ZONE_FADE);
+            canvas.draw_arc_aa(cx, cy, radius, thickness, from, to, color)?;
+        }
+
+        let (fill, text) = if self.state.enabled {
+            (reading.color(reading.shown, style.accent), style.foreground)
+        } else {
+            (style.disabled, style.disabled)
+        };
+        let fraction = reading.fraction(reading.shown);
+        if fraction > 0.0 {
+            canvas.draw_arc_aa(
+                cx,
+                cy,
+                radius,
+                thickness,
+                START_ANGLE,
+                angle(fraction),
+                fill,
+            )?;
+        }
+
+        let value = reading.format(reading.shown);
+        draw_centered(canvas, style, &value, self.state.rect, cy, text)?;
+        if let Some(label) = &self.label {
+            let y = cy + radius * 0.6;
+            draw_centered(canvas, style, label, self.state.rect, y, style.disabled)?;
+        }
+        Ok(())
+    }
+}
+
+impl Widget for Gauge {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let rect = self.state.rect;
+        canvas.push_clip(rect);
+        let result = self.draw_gauge(canvas, style);
+        canvas.pop_clip();
+        result
+    }
+
+    fn animate(&mut self, now: Instant) {
+        if self.reading.animate(now) {
+            self.state.dirty = true;
+        }
+    }
+}
+
+/// Round dial with a needle, ticks and the range at its ends
+pub struct Dial {
+    state: WidgetState,
+    reading: Reading,
+    label: Option<String>,
+    /// Intervals between ticks
+    ticks: u16,
+}
+
+impl Dial {
+    pub fn new(rect: Rect, min: f32, max: f32, value: f32) -> Self {
+        Self {
+            state: WidgetState::new(rect),
+            reading: Reading::new(min, max, value),
+            label: None,
+            ticks: 10,
+        }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Divides the scale into `ticks` intervals, zero draws no ticks
+    pub fn with_ticks(mut self, ticks: u16) -> Self {
+        self.ticks = ticks;
+        self
+    }
+
+    pub fn with_zone(mut self, zone: Zone) -> Self {
+        self.reading.zones.push(zone);
+        self
+    }
+
+    /// Draws the needle in `color` from `at` up to the next threshold
+    pub fn with_threshold(mut self, at: f32, color: Color) -> Self {
+        self.reading.add_threshold(at, color);
+        self
+    }
+
+    /// Appended to the value, e.g. "C" or "kW"
+    pub fn with_unit(mut self, unit: impl Into<String>) -> Self {
+        self.reading.unit = unit.into();
+        self
+    }
+
+    pub fn with_decimals(mut self, decimals: usize) -> Self {
+        self.reading.decimals = decimals;
+        self
+    }
+
+    /// How long value changes take to show, zero jumps
+    pub fn with_animation(mut self, duration: Duration) -> Self {
+        self.reading.duration = duration;
+        self
+    }
+
+    /// How the shown value moves to a new one, eased out unless changed
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.reading.easing = easing;
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.reading.value
+    }
+
+    pub fn range(&self) -> (f32, f32) {
+        (self.reading.min, self.reading.max)
+    }
+
+    /// Sets the value, clamped to the range, the needle swings there over time
+    pub fn set_value(&mut self, value: f32) {
+        if self.reading.set_value(value) {
+            self.state.dirty = true;
+        }
+    }
+
+    fn draw_dial(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let reading = &self.reading;
+        let rect = self.state.rect;
+        let (cx, cy, outer) = circle(rect);
+        let band = (outer / 10.0).max(2.0);
+        let rim = outer - band / 2.0;
+        let point = |angle: f32, radius: f32| {
+            let angle = angle.to_radians();
+            (cx + radius * angle.cos(), cy + radius * angle.sin())
+        };
+
+        for zone in &reading.zones {
+            let (from, to) = (
+                angle(reading.fraction(zone.from)),
+                angle(reading.fraction(zone.to)),
+            );
+            canvas.draw_arc_aa(cx, cy, rim, band, from, to, zone.color)?;
+        }
+        let tick_color = if self.state.enabled {
+            style.foreground
+        } else {
+            style.disabled
+        };
+        for tick in 0..=self.ticks {
+            let angle = angle(tick as f32 / self.ticks.max(1) as f32);
+            let (x0, y0) = point(angle, outer - band * 2.5);
+            let (x1, y1) = point(angle, outer - band);
+            canvas.draw_line_aa(x0, y0, x1, y1, tick_color)?;
+        }
+
+        // The range below the ends of the scale
+        let height = style.font.line_metrics().line_height;
+        let y = (rect.bottom() - 1 - height / 2) as f32;
+        let half = Rect::new(rect.x, rect.y, rect.w / 2, rect.h);
+        draw_centered(
+            canvas,
+            style,
+            &reading.format(reading.min),
+            half,
+            y,
+            style.disabled,
+        )?;
+        let half = Rect::new(rect.x + half.w as i32, rect.y, rect.w - half.w, rect.h);
+        draw_centered(
+            canvas,
+            style,
+            &reading.format(reading.max),
+            half,
+            y,
+            style.disabled,
+        )?;
+
+        let value = reading.format(reading.shown);
+        draw_centered(canvas, style, &value, rect, cy + outer * 0.45, tick_color)?;
+        if let Some(label) = &self.label {
+            draw_centered(canvas, style, label, rect, cy - outer * 0.4, style.disabled)?;
+        }
+
+        let needle = if self.state.enabled {
+            reading.color(reading.shown, style.accent)
+        } else {
+            style.disabled
+        };
+        let (x, y) = point(angle(reading.fraction(reading.shown)), outer - band * 2.0);
+        let width = (outer / 30.0).max(2.0);
+        canvas.draw_thick_line_aa(cx, cy, x, y, width, needle)?;
+        canvas.fill_circle_aa(cx, cy, width * 2.0, tick_color)
+    }
+}
+
+impl Widget for Dial {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        canvas.push_clip(self.state.rect);
+        let result = self.draw_dial(canvas, style);
+        canvas.pop_clip();
+        result
+    }
+
+    fn animate(&mut self, now: Instant) {
+        if self.reading.animate(now) {
+            self.state.dirty = true;
+        }
+    }
+}
+
+/// Bar of segments lit up to the value, like an LED level meter
+///
+/// Fills left to right when wider than tall, else bottom to top. Lit segments take
+/// the color of the zone or threshold they are in.
+pub struct Meter {
+    state: WidgetState,
+    reading: Reading,
+    segments: u16,
+    gap: u16,
+}
+
+impl Meter {
+    pub fn new(rect: Rect, min: f32, max: f32, value: f32) -> Self {
+        Self {
+            state: WidgetState::new(rect),
+            reading: Reading::new(min, max, value),
+            segments: 10,
+            gap: 2,
+        }
+    }
+
+    /// Number of segments and the space between them
+    pub fn with_segments(mut self, segments: u16, gap: u16) -> Self {
+        self.segments = segments.max(1);
+        self.gap = gap;
+        self
+    }
+
+    pub fn with_zone(mut self, zone: Zone) -> Self {
+        self.reading.zones.push(zone);
+        self
+    }
+
+    /// Lights segments from `at` up to the next threshold in `color`
+    pub fn with_threshold(mut self, at: f32, color: Color) -> Self {
+        self.reading.add_threshold(at, color);
+        self
+    }
+
+    /// How long value changes take to show, zero jumps
+    pub fn with_animation(mut self, duration: Duration) -> Self {
+        self.reading.duration = duration;
+        self
+    }
+
+    /// How the shown value moves to a new one, eased out unless changed
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.reading.easing = easing;
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.reading.value
+    }
+
+    pub fn range(&self) -> (f32, f32) {
+        (self.reading.min, self.reading.max)
+    }
+
+    /// Sets the value, clamped to the range, the meter moves there over time
+    pub fn set_value(&mut self, value: f32) {
+        if self.reading.set_value(value) {
+            self.state.dirty = true;
+        }
+    }
+
+    fn draw_segments(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let reading = &self.reading;
+        let lit = reading.fraction(reading.shown) * self.segments as f32;
+        let radius = style.corner_radius.min(2);
+
+        for index in 0..self.segments {
+            // A segment lights once the value is at least halfway through it
+            let middle = (index as f32 + 0.5) / self.segments as f32;
+            let value = reading.min + middle * (reading.max - reading.min);
+            let color = match (self.state.enabled, index as f32 + 0.5 <= lit) {
+                (false, true) => style.disabled,
+                (true, true) => reading.color(value, style.accent),
+                (_, false) => style.surface,
+            };
+            canvas.fill_round_rect(self.segment(index), radius, color)?;
+        }
+        Ok(())
+    }
+
+    /// Rectangle of segment `index`, counted from the empty end
+    fn segment(&self, index: u16) -> Rect {
+        let rect = self.state.rect;
+        let horizontal = rect.w >= rect.h;
+        let length = if horizontal { rect.w } else { rect.h } as u32;
+        let count = self.segments as u32;
+        let gaps = self.gap as u32 * (count - 1);
+        let from =
+            length.saturating_sub(gaps) * index as u32 / count + self.gap as u32 * index as u32;
+        let to = length.saturating_sub(gaps) * (index as u32 + 1) / count
+            + self.gap as u32 * index as u32;
+        let size = (to - from) as u16;
+        if horizontal {
+            Rect::new(rect.x + from as i32, rect.y, size, rect.h)
+        } else {
+            Rect::new(rect.x, rect.bottom() - to as i32, rect.w, size)
+        }
+    }
+}
+
+impl Widget for Meter {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        canvas.push_clip(self.state.rect);
+        let result = self.draw_segments(canvas, style);
+        canvas.pop_clip();
+        result
+    }
+
+    fn animate(&mut self, now: Instant) {
+        if self.reading.animate(now) {
+            self.state.dirty = true;
+        }
+    }
+}
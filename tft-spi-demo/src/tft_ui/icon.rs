@@ -0,0 +1,91 @@
+use crate::tft_display::{
+    canvas::Canvas, color::Color, geometry::Rect, sprite::Sprite, tft_display::Result,
+};
+use crate::tft_touch::event::TouchEvent;
+use crate::tft_ui::{
+    style::Style,
+    widget::{Widget, WidgetEvent, WidgetState},
+};
+
+/// How far a disabled icon is faded towards the background
+const DISABLED_FADE: f32 = 0.6;
+
+/// Sprite centered in its rectangle, reports `Clicked`
+pub struct Icon {
+    state: WidgetState,
+    sprite: Sprite,
+}
+
+impl Icon {
+    pub fn new(rect: Rect, sprite: Sprite) -> Self {
+        Self {
+            state: WidgetState::new(rect),
+            sprite,
+        }
+    }
+
+    pub fn sprite(&self) -> &Sprite {
+        &self.sprite
+    }
+
+    pub fn set_sprite(&mut self, sprite: Sprite) {
+        self.sprite = sprite;
+        self.state.dirty = true;
+    }
+}
+
+impl Widget for Icon {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let rect = self.state.rect;
+        let background = if self.state.pressed {
+            style.pressed
+        } else {
+            style.background
+        };
+        if self.state.pressed {
+            canvas.fill_round_rect(rect, style.corner_radius, background)?;
+        }
+
+        let x = rect.x + (rect.w as i32 - self.sprite.width() as i32) / 2;
+        let y = rect.y + (rect.h as i32 - self.sprite.height() as i32) / 2;
+        if self.state.enabled {
+            return canvas.draw_sprite(&self.sprite, x, y);
+        }
+
+        let faded: Vec<Color> = self
+            .sprite
+            .pixels()
+            .iter()
+            .map(|pixel| pixel.lerp(background, DISABLED_FADE))
+            .collect();
+        // Faded colors may hit the color key, so keep the visible pixels as a mask
+        let (w, h) = (self.sprite.width(), self.sprite.height());
+        let mut sprite = Sprite::new(w, h, faded)?;
+        if !self.sprite.is_opaque() {
+            let mask = (0..h)
+                .flat_map(|y| (0..w).map(move |x| (x, y)))
+                .map(|(x, y)| self.sprite.is_visible(x, y))
+                .collect();
+            sprite = sprite.with_mask(mask)?;
+        }
+        canvas.draw_sprite(&sprite, x, y)
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn touch(&mut self, event: TouchEvent) -> Option<WidgetEvent> {
+        self.state
+            .track_press(event)
+            .then_some(WidgetEvent::Clicked)
+    }
+}
@@ -0,0 +1,290 @@
+use crate::tft_display::{canvas::Canvas, enums::Align, geometry::Rect, tft_display::Result};
+use crate::tft_touch::event::TouchEvent;
+use crate::tft_ui::{
+    style::Style,
+    widget::{self, Widget, WidgetEvent, WidgetState},
+};
+
+/// Gap between keys in pixels
+const KEY_SPACING: u16 = 3;
+/// Width of a letter key in layout units, every row is `ROW_UNITS` wide
+const KEY: u16 = 2;
+const ROW_UNITS: u16 = 20;
+
+/// What a keyboard reports with `WidgetEvent::Key`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyInput {
+    Char(char),
+    Backspace,
+    Enter,
+    /// Cursor one character back
+    Left,
+    /// Cursor one character forward
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Key {
+    Input(KeyInput),
+    Shift,
+    /// Switches to the symbol layer
+    Symbols,
+    /// Switches back to the letters
+    Letters,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Layer {
+    Letters,
+    Symbols,
+    Numeric,
+}
+
+/// State of the shift key, pressing it cycles through them
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Shift {
+    #[default]
+    Off,
+    /// For the next letter only
+    Once,
+    Locked,
+}
+
+/// On-screen QWERTY keyboard or numeric keypad, reports `Key`
+///
+/// The QWERTY layout has shift and symbol layers, switching them is handled by the
+/// keyboard. Keys fire on release, so sliding off a key cancels it.
+pub struct Keyboard {
+    state: WidgetState,
+    layer: Layer,
+    shift: Shift,
+    /// Key under the finger, index into `keys`
+    pressed: Option<usize>,
+}
+
+impl Keyboard {
+    /// QWERTY keyboard with shift and symbols
+    pub fn qwerty(rect: Rect) -> Self {
+        Self::with_layer(rect, Layer::Letters)
+    }
+
+    /// Digits, `-` and `.` with cursor keys, e.g. for PINs and addresses
+    pub fn numeric(rect: Rect) -> Self {
+        Self::with_layer(rect, Layer::Numeric)
+    }
+
+    fn with_layer(rect: Rect, layer: Layer) -> Self {
+        Self {
+            state: WidgetState::new(rect),
+            layer,
+            shift: Shift::Off,
+            pressed: None,
+        }
+    }
+
+    /// Rows of keys with their width in layout units
+    fn rows(&self) -> Vec<Vec<(Key, u16)>> {
+        let chars = |chars: &str| -> Vec<(Key, u16)> {
+            let upper = self.shift != Shift::Off;
+            chars
+                .chars()
+                .map(|c| if upper { c.to_ascii_uppercase() } else { c })
+                .map(|c| (Key::Input(KeyInput::Char(c)), KEY))
+                .collect()
+        };
+        let input = |input: KeyInput, width: u16| (Key::Input(input), width);
+        let bottom = |switch: Key| {
+            vec![
+                (switch, 3),
+                input(KeyInput::Char(','), KEY),
+                input(KeyInput::Char(' '), 8),
+                input(KeyInput::Char('.'), KEY),
+                input(KeyInput::Enter, 5),
+            ]
+        };
+
+        match self.layer {
+            Layer::Letters => vec![
+                chars("qwertyuiop"),
+                chars("asdfghjkl"),
+                [
+                    vec![(Key::Shift, 3)],
+                    chars("zxcvbnm"),
+                    vec![input(KeyInput::Backspace, 3)],
+                ]
+                .concat(),
+                bottom(Key::Symbols),
+            ],
+            Layer::Symbols => vec![
+                chars("1234567890"),
+                chars("@#$%&*-+()"),
+                [chars("!\"':;/?_"), vec![input(KeyInput::Backspace, 4)]].concat(),
+                bottom(Key::Letters),
+            ],
+            Layer::Numeric => {
+                let digit = |c| input(KeyInput::Char(c), 5);
+                vec![
+                    vec![
+                        digit('1'),
+                        digit('2'),
+                        digit('3'),
+                        input(KeyInput::Backspace, 5),
+                    ],
+                    vec![digit('4'), digit('5'), digit('6'), input(KeyInput::Left, 5)],
+                    vec![
+                        digit('7'),
+                        digit('8'),
+                        digit('9'),
+                        input(KeyInput::Right, 5),
+                    ],
+                    vec![
+                        digit('-'),
+                        digit('0'),
+                        digit('.'),
+                        input(KeyInput::Enter, 5),
+                    ],
+                ]
+            }
+        }
+    }
+
+    /// Every key with its rectangle, rows narrower than the keyboard are centered
+    fn keys(&self) -> Vec<(Key, Rect)> {
+        let rect = self.state.rect;
+        let rows = self.rows();
+        let count = rows.len() as u32;
+        let height = (rect.h as u32).saturating_sub(KEY_SPACING as u32 * (count - 1));
+        let span = |start: i32, total: u32, from: u32, to: u32, parts: u32| {
+            let (a, b) = (total * from / parts, total * to / parts);
+            (start + a as i32, (b - a) as u16)
+        };
+
+        let mut keys = Vec::new();
+        for (row, row_keys) in rows.into_iter().enumerate() {
+            let row = row as u32;
+            let (y, h) = span(rect.y, height, row, row + 1, count);
+            let y = y + (row * KEY_SPACING as u32) as i32;
+
+            let units: u16 = row_keys.iter().map(|(_, width)| width).sum();
+            let mut unit = (ROW_UNITS.saturating_sub(units) / 2) as u32;
+            for (key, width) in row_keys {
+                let (x, w) = span(
+                    rect.x,
+                    rect.w as u32,
+                    unit,
+                    unit + width as u32,
+                    ROW_UNITS as u32,
+                );
+                unit += width as u32;
+                // Half the spacing on either side, so centered rows stay centered
+                let w = w.saturating_sub(KEY_SPACING);
+                keys.push((key, Rect::new(x + KEY_SPACING as i32 / 2, y, w, h)));
+            }
+        }
+        keys
+    }
+
+    fn label(&self, key: Key) -> String {
+        match key {
+            Key::Input(KeyInput::Char(' ')) => String::new(),
+            Key::Input(KeyInput::Char(c)) => c.to_string(),
+            Key::Input(KeyInput::Backspace) => "Del".into(),
+            Key::Input(KeyInput::Enter) => "OK".into(),
+            Key::Input(KeyInput::Left) => "<".into(),
+            Key::Input(KeyInput::Right) => ">".into(),
+            Key::Shift => "^".into(),
+            Key::Symbols => "123".into(),
+            Key::Letters => "ABC".into(),
+        }
+    }
+
+    /// Handles a released key, `Some` for keys that produce input
+    fn activate(&mut self, key: Key) -> Option<KeyInput> {
+        self.state.dirty = true;
+        match key {
+            Key::Shift => {
+                self.shift = match self.shift {
+                    Shift::Off => Shift::Once,
+                    Shift::Once => Shift::Locked,
+                    Shift::Locked => Shift::Off,
+                };
+                None
+            }
+            Key::Symbols => {
+                self.layer = Layer::Symbols;
+                self.shift = Shift::Off;
+                None
+            }
+            Key::Letters => {
+                self.layer = Layer::Letters;
+                None
+            }
+            Key::Input(input) => {
+                if self.shift == Shift::Once && matches!(input, KeyInput::Char(_)) {
+                    self.shift = Shift::Off;
+                }
+                Some(input)
+            }
+        }
+    }
+}
+
+impl Widget for Keyboard {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let radius = style.corner_radius / 2;
+        for (index, (key, rect)) in self.keys().into_iter().enumerate() {
+            let active = match key {
+                Key::Shift => self.shift != Shift::Off,
+                Key::Input(KeyInput::Enter) => true,
+                _ => false,
+            };
+            let (face, text) = match (self.state.enabled, self.pressed == Some(index)) {
+                (false, _) => (style.surface, style.disabled),
+                (true, true) => (style.pressed, style.foreground),
+                (true, false) if active => (style.accent, style.foreground),
+                (true, false) => (style.surface, style.foreground),
+            };
+            canvas.fill_round_rect(rect, radius, face)?;
+            if self.shift == Shift::Locked && key == Key::Shift {
+                // Caps lock gets an underline to tell it from a single shift
+                let line = Rect::new(rect.x + 4, rect.bottom() - 4, rect.w.saturating_sub(8), 2);
+                canvas.fill_rect(line, text)?;
+            }
+            widget::draw_text_block(canvas, style, &self.label(key), rect, Align::Center, text)?;
+        }
+        Ok(())
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn touch(&mut self, event: TouchEvent) -> Option<WidgetEvent> {
+        let point = event.point();
+        let keys = self.keys();
+        let under = keys.iter().position(|(_, rect)| rect.contains_point(point));
+
+        let pressed = match event {
+            TouchEvent::Up(_) => None,
+            _ => under,
+        };
+        if self.pressed != pressed {
+            self.pressed = pressed;
+            self.state.dirty = true;
+        }
+
+        let TouchEvent::Up(_) = event else {
+            return None;
+        };
+        let (key, _) = keys[under?];
+        self.activate(key).map(WidgetEvent::Key)
+    }
+}
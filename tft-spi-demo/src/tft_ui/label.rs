@@ -0,0 +1,82 @@
+use crate::tft_display::{
+    canvas::Canvas, color::Color, enums::Align, geometry::Rect, tft_display::Result,
+};
+use crate::tft_ui::{
+    style::Style,
+    widget::{self, Widget, WidgetState},
+};
+
+/// Static text, wrapped to its rectangle and centered vertically
+pub struct Label {
+    state: WidgetState,
+    text: String,
+    align: Align,
+    /// Text color instead of the style's foreground
+    color: Option<Color>,
+}
+
+impl Label {
+    pub fn new(rect: Rect, text: impl Into<String>) -> Self {
+        Self {
+            state: WidgetState::new(rect),
+            text: text.into(),
+            align: Align::Left,
+            color: None,
+        }
+    }
+
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        if self.text != text {
+            self.text = text;
+            self.state.dirty = true;
+        }
+    }
+
+    pub fn set_color(&mut self, color: Option<Color>) {
+        if self.color != color {
+            self.color = color;
+            self.state.dirty = true;
+        }
+    }
+}
+
+impl Widget for Label {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let color = if self.state.enabled {
+            self.color.unwrap_or(style.foreground)
+        } else {
+            style.disabled
+        };
+        widget::draw_text_block(
+            canvas,
+            style,
+            &self.text,
+            self.state.rect,
+            self.align,
+            color,
+        )
+    }
+}
@@ -0,0 +1,224 @@
+use crate::tft_display::{enums::Align, geometry::Rect};
+use crate::tft_ui::ui::WidgetId;
+
+/// Space a node takes along the main axis of the row or column holding it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Size {
+    /// Exactly this many pixels
+    Fixed(u16),
+    /// A share of what the fixed siblings leave, proportional to the weight
+    Flex(u16),
+}
+
+impl Default for Size {
+    fn default() -> Self {
+        Size::Flex(1)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Kind {
+    Widget(WidgetId),
+    Space,
+    Row(Vec<Layout>),
+    Column(Vec<Layout>),
+    Stack(Vec<Layout>),
+    Grid(u16, Vec<Layout>),
+}
+
+/// Tree of rows, columns, stacks and grids that places widgets in an area
+///
+/// Rows and columns split their main axis between the children by `Size` and give
+/// each the full cross axis. Without flexible children, `align` places the children
+/// at the start (`Left`), center or end (`Right`) of the main axis. Stacks give every
+/// child the whole area, grids split it into equal cells filled row by row.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layout {
+    kind: Kind,
+    size: Size,
+    padding: u16,
+    spacing: u16,
+    align: Align,
+}
+
+impl Layout {
+    /// The area of a widget added to the `Ui`
+    pub fn widget(id: WidgetId) -> Self {
+        Self::with_kind(Kind::Widget(id))
+    }
+
+    /// Empty space, e.g. to push siblings apart
+    pub fn space() -> Self {
+        Self::with_kind(Kind::Space)
+    }
+
+    /// Children side by side, left to right
+    pub fn row(children: impl IntoIterator<Item = Layout>) -> Self {
+        Self::with_kind(Kind::Row(children.into_iter().collect()))
+    }
+
+    /// Children on top of each other, top to bottom
+    pub fn column(children: impl IntoIterator<Item = Layout>) -> Self {
+        Self::with_kind(Kind::Column(children.into_iter().collect()))
+    }
+
+    /// Children overlapping, each taking the whole area
+    pub fn stack(children: impl IntoIterator<Item = Layout>) -> Self {
+        Self::with_kind(Kind::Stack(children.into_iter().collect()))
+    }
+
+    /// Children in equal cells, `columns` per row
+    pub fn grid(columns: u16, children: impl IntoIterator<Item = Layout>) -> Self {
+        Self::with_kind(Kind::Grid(columns.max(1), children.into_iter().collect()))
+    }
+
+    /// Takes exactly `size` pixels in the parent row or column
+    pub fn fixed(mut self, size: u16) -> Self {
+        self.size = Size::Fixed(size);
+        self
+    }
+
+    /// Takes a share of the free space in the parent row or column, the default
+    pub fn flex(mut self, weight: u16) -> Self {
+        self.size = Size::Flex(weight);
+        self
+    }
+
+    /// Space kept free inside every edge
+    pub fn padding(mut self, padding: u16) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Gap between the children
+    pub fn spacing(mut self, spacing: u16) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Where children go along the main axis when they don't fill it
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Rectangle of every widget in the tree when laid out in `area`
+    pub fn rects(&self, area: Rect) -> Vec<(WidgetId, Rect)> {
+        let mut rects = Vec::new();
+        self.place(area, &mut rects);
+        rects
+    }
+
+    fn with_kind(kind: Kind) -> Self {
+        Self {
+            kind,
+            size: Size::default(),
+            padding: 0,
+            spacing: 0,
+            align: Align::Left,
+        }
+    }
+
+    fn place(&self, area: Rect, rects: &mut Vec<(WidgetId, Rect)>) {
+        let area = area.inset(self.padding);
+        match &self.kind {
+            Kind::Widget(id) => rects.push((*id, area)),
+            Kind::Space => {}
+            Kind::Row(children) => {
+                for (child, (x, w)) in children.iter().zip(self.split(children, area.x, area.w)) {
+                    child.place(Rect::new(x, area.y, w, area.h), rects);
+                }
+            }
+            Kind::Column(children) => {
+                for (child, (y, h)) in children.iter().zip(self.split(children, area.y, area.h)) {
+                    child.place(Rect::new(area.x, y, area.w, h), rects);
+                }
+            }
+            Kind::Stack(children) => {
+                for child in children {
+                    child.place(area, rects);
+                }
+            }
+            Kind::Grid(columns, children) => {
+                let rows = children.len().div_ceil(*columns as usize) as u16;
+                let xs = cells(area.x, area.w, *columns, self.spacing);
+                let ys = cells(area.y, area.h, rows, self.spacing);
+                for (i, child) in children.iter().enumerate() {
+                    let (x, w) = xs[i % *columns as usize];
+                    let (y, h) = ys[i / *columns as usize];
+                    child.place(Rect::new(x, y, w, h), rects);
+                }
+            }
+        }
+    }
+
+    /// Start and length of each child along a main axis starting at `start`
+    fn split(&self, children: &[Layout], start: i32, length: u16) -> Vec<(i32, u16)> {
+        let gaps = self.spacing as u32 * children.len().saturating_sub(1) as u32;
+        let available = (length as u32).saturating_sub(gaps);
+        let fixed: u32 = children
+            .iter()
+            .filter_map(|child| match child.size {
+                Size::Fixed(size) => Some(size as u32),
+                Size::Flex(_) => None,
+            })
+            .sum();
+        let weights: u32 = children
+            .iter()
+            .filter_map(|child| match child.size {
+                Size::Flex(weight) => Some(weight as u32),
+                Size::Fixed(_) => None,
+            })
+            .sum();
+        let free = available.saturating_sub(fixed);
+
+        // Only free space nobody flexes into is left for alignment
+        let used = if weights > 0 {
+            available
+        } else {
+            fixed.min(available)
+        };
+        let offset = match self.align {
+            Align::Left => 0,
+            Align::Center => (available - used) / 2,
+            Align::Right => available - used,
+        };
+
+        // Flexible lengths from running totals, so rounding never leaves a gap
+        let mut position = start + offset as i32;
+        let mut flexed = 0;
+        children
+            .iter()
+            .map(|child| {
+                let size = match child.size {
+                    Size::Fixed(size) => size as u32,
+                    Size::Flex(weight) => {
+                        let before = free * flexed / weights;
+                        flexed += weight as u32;
+                        free * flexed / weights - before
+                    }
+                };
+                let span = (position, size as u16);
+                position += (size + self.spacing as u32) as i32;
+                span
+            })
+            .collect()
+    }
+}
+
+/// Equal cells with `spacing` between them, as start and length
+fn cells(start: i32, length: u16, count: u16, spacing: u16) -> Vec<(i32, u16)> {
+    let count = count.max(1) as u32;
+    let available = (length as u32).saturating_sub(spacing as u32 * (count - 1));
+    (0..count)
+        .map(|i| {
+            let (from, to) = (available * i / count, available * (i + 1) / count);
+            let x = start + (from + i * spacing as u32) as i32;
+            (x, (to - from) as u16)
+        })
+        .collect()
+}
@@ -0,0 +1,374 @@
+use std::time::{Duration, Instant};
+
+use crate::tft_display::{
+    canvas::Canvas, enums::Align, geometry::Point, geometry::Rect, tft_display::Result,
+};
+use crate::tft_touch::event::TouchEvent;
+use crate::tft_ui::{
+    style::Style,
+    widget::{self, Widget, WidgetEvent, WidgetState},
+};
+
+/// Movement in pixels before a touch scrolls instead of pressing an item
+const DRAG_THRESHOLD: i32 = 8;
+/// Fraction of the momentum lost per second is `1 - exp(-FRICTION)`
+const FRICTION: f32 = 3.0;
+/// Momentum below this many pixels per second stops
+const MIN_VELOCITY: f32 = 20.0;
+/// A finger resting this long before lifting leaves no momentum
+const REST_TIME: Duration = Duration::from_millis(100);
+/// Width of the scroll indicator's thumb and the margin right of it
+const INDICATOR_WIDTH: u16 = 4;
+const INDICATOR_MARGIN: u16 = 2;
+/// Shortest thumb, so very long lists still show one
+const MIN_THUMB: u16 = 16;
+/// Width of the bar marking the selected item
+const SELECTED_WIDTH: u16 = 4;
+
+/// One row of a `ListView`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListItem {
+    pub text: String,
+    /// Shown right aligned in a dimmer color
+    pub detail: Option<String>,
+}
+
+impl ListItem {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            detail: None,
+        }
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+impl From<&str> for ListItem {
+    fn from(text: &str) -> Self {
+        Self::new(text)
+    }
+}
+
+impl From<String> for ListItem {
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+/// Touch that may turn into a drag
+#[derive(Clone, Copy, Debug)]
+struct Drag {
+    start_y: i32,
+    start_offset: f32,
+    last_y: i32,
+    last_time: Instant,
+    dragging: bool,
+}
+
+/// Vertically scrolling list of equally tall items, reports `Selected` on a tap
+///
+/// Only the visible items are drawn, so lists can be long. Dragging scrolls with
+/// momentum and a scroll indicator shows the position. When the list covers the
+/// display's scroll area, `Ui::flush` lets the controller scroll it and draws only the
+/// rows moved in, see `TftDisplay::set_scroll_area` and
+/// `ScreenManager::set_scroll_widget`. That takes a full-width list in portrait.
+pub struct ListView {
+    state: WidgetState,
+    items: Vec<ListItem>,
+    item_height: u16,
+    /// Pixels of content scrolled out at the top
+    offset: f32,
+    /// `offset` rounded, as last drawn
+    drawn: i32,
+    /// Rows moved up since `take_scroll`
+    scrolled: i32,
+    /// Momentum in pixels per second, positive scrolls towards the end
+    velocity: f32,
+    drag: Option<Drag>,
+    /// Item under the finger until it moves
+    pressed: Option<usize>,
+    selected: Option<usize>,
+    last_animate: Option<Instant>,
+}
+
+impl ListView {
+    pub fn new(rect: Rect, items: Vec<ListItem>) -> Self {
+        Self {
+            state: WidgetState::new(rect),
+            items,
+            item_height: 40,
+            offset: 0.0,
+            drawn: 0,
+            scrolled: 0,
+            velocity: 0.0,
+            drag: None,
+            pressed: None,
+            selected: None,
+            last_animate: None,
+        }
+    }
+
+    pub fn with_item_height(mut self, height: u16) -> Self {
+        self.item_height = height.max(1);
+        self.set_offset(self.offset);
+        self
+    }
+
+    pub fn items(&self) -> &[ListItem] {
+        &self.items
+    }
+
+    /// Replaces the items, keeping the position as far as it still fits
+    pub fn set_items(&mut self, items: Vec<ListItem>) {
+        self.items = items;
+        self.pressed = None;
+        self.selected = self.selected.filter(|&index| index < self.items.len());
+        self.set_offset(self.offset);
+        self.state.dirty = true;
+    }
+
+    pub fn push(&mut self, item: impl Into<ListItem>) {
+        self.items.push(item.into());
+        self.state.dirty = true;
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn set_selected(&mut self, index: Option<usize>) {
+        let index = index.filter(|&index| index < self.items.len());
+        if self.selected != index {
+            self.selected = index;
+            self.state.dirty = true;
+        }
+    }
+
+    /// Pixels of content scrolled out at the top
+    pub fn offset(&self) -> f32 {
+        self.offset
+    }
+
+    /// Scrolls to `offset`, clamped to the content, and stops any momentum
+    pub fn set_offset(&mut self, offset: f32) {
+        self.velocity = 0.0;
+        self.move_to(offset);
+    }
+
+    /// Scrolls just enough to show the item at `index` completely
+    pub fn scroll_to(&mut self, index: usize) {
+        let top = index.min(self.items.len()) as f32 * self.item_height as f32;
+        let bottom = top + self.item_height as f32 - self.state.rect.h as f32;
+        self.set_offset(self.offset.max(bottom).min(top));
+    }
+
+    /// Largest offset, where the last item sits at the bottom
+    pub fn max_offset(&self) -> f32 {
+        let content = self.items.len() as f32 * self.item_height as f32;
+        (content - self.state.rect.h as f32).max(0.0)
+    }
+
+    fn move_to(&mut self, offset: f32) {
+        self.offset = offset.clamp(0.0, self.max_offset());
+        let drawn = self.offset.round() as i32;
+        self.scrolled += drawn - self.drawn;
+        self.drawn = drawn;
+    }
+
+    fn item_at(&self, point: Point) -> Option<usize> {
+        let rect = self.state.rect;
+        if !rect.contains_point(point) {
+            return None;
+        }
+        let index = (point.y - rect.y + self.drawn) / self.item_height as i32;
+        (index >= 0 && (index as usize) < self.items.len()).then_some(index as usize)
+    }
+
+    fn item_rect(&self, index: usize) -> Rect {
+        let rect = self.state.rect;
+        let y = rect.y + index as i32 * self.item_height as i32 - self.drawn;
+        Rect::new(rect.x, y, rect.w, self.item_height)
+    }
+
+    /// Column along the right edge the thumb moves in
+    fn indicator_column(&self) -> Rect {
+        let rect = self.state.rect;
+        let width = (INDICATOR_WIDTH + INDICATOR_MARGIN).min(rect.w);
+        Rect::new(rect.right() - width as i32, rect.y, width, rect.h)
+    }
+
+    /// The scroll indicator's thumb, `None` while everything fits
+    fn thumb(&self) -> Option<Rect> {
+        let max = self.max_offset();
+        if max <= 0.0 {
+            return None;
+        }
+        let rect = self.state.rect;
+        let content = max + rect.h as f32;
+        let height = ((rect.h as f32 * rect.h as f32 / content) as u16)
+            .max(MIN_THUMB)
+            .min(rect.h);
+        let y = rect.y + ((rect.h - height) as f32 * self.offset / max).round() as i32;
+        let column = self.indicator_column();
+        let width = INDICATOR_WIDTH.min(column.w);
+        Some(Rect::new(column.x, y, width, height))
+    }
+}
+
+impl Widget for ListView {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let rect = self.state.rect;
+        let Some(area) = canvas.clip().intersect(rect) else {
+            return Ok(());
+        };
+        canvas.fill_rect(area, style.background)?;
+
+        // Only the items in the clip, often just the rows scrolled in
+        let height = self.item_height as i32;
+        let first = (area.y - rect.y + self.drawn) / height;
+        let last = (area.bottom() - 1 - rect.y + self.drawn) / height;
+        let detail_color = style.foreground.lerp(style.background, 0.4);
+        for index in first.max(0) as usize..=last.max(0) as usize {
+            let Some(item) = self.items.get(index) else {
+                break;
+            };
+            let row = self.item_rect(index);
+            let (face, text) = match (self.state.enabled, self.pressed == Some(index)) {
+                (false, _) => (style.background, style.disabled),
+                (true, true) => (style.pressed, style.foreground),
+                (true, false) => (style.background, style.foreground),
+            };
+            if face != style.background {
+                canvas.fill_rect(row, face)?;
+            }
+            if self.selected == Some(index) {
+                canvas.fill_rect(Rect::new(row.x, row.y, SELECTED_WIDTH, row.h), style.accent)?;
+            }
+            canvas.fill_rect(Rect::new(row.x, row.bottom() - 1, row.w, 1), style.surface)?;
+
+            let content = row.inset(style.padding);
+            widget::draw_text_block(canvas, style, &item.text, content, Align::Left, text)?;
+            if let Some(detail) = &item.detail {
+                let color = if self.state.enabled {
+                    detail_color
+                } else {
+                    text
+                };
+                widget::draw_text_block(canvas, style, detail, content, Align::Right, color)?;
+            }
+        }
+
+        if let Some(thumb) = self.thumb() {
+            canvas.fill_round_rect(thumb, INDICATOR_WIDTH / 2, style.disabled)?;
+        }
+        Ok(())
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn touch(&mut self, event: TouchEvent) -> Option<WidgetEvent> {
+        let now = Instant::now();
+        let point = event.point();
+        let pressed = match event {
+            TouchEvent::Down(_) => {
+                self.velocity = 0.0;
+                self.drag = Some(Drag {
+                    start_y: point.y,
+                    start_offset: self.offset,
+                    last_y: point.y,
+                    last_time: now,
+                    dragging: false,
+                });
+                self.item_at(point)
+            }
+            TouchEvent::Move(_) => {
+                let mut drag = self.drag?;
+                drag.dragging |= (point.y - drag.start_y).abs() >= DRAG_THRESHOLD;
+                if drag.dragging {
+                    self.move_to(drag.start_offset + (drag.start_y - point.y) as f32);
+                    let dt = (now - drag.last_time).as_secs_f32();
+                    if dt > 0.0 {
+                        // Smoothed, single touch samples are noisy
+                        let velocity = (drag.last_y - point.y) as f32 / dt;
+                        self.velocity = (self.velocity + velocity) / 2.0;
+                    }
+                    drag.last_y = point.y;
+                    drag.last_time = now;
+                }
+                self.drag = Some(drag);
+                self.pressed.filter(|_| !drag.dragging)
+            }
+            TouchEvent::Up(_) => None,
+        };
+        if self.pressed != pressed {
+            self.pressed = pressed;
+            self.state.dirty = true;
+        }
+
+        let TouchEvent::Up(_) = event else {
+            return None;
+        };
+        let drag = self.drag.take()?;
+        if drag.dragging {
+            if now - drag.last_time > REST_TIME {
+                self.velocity = 0.0;
+            }
+            self.last_animate = Some(now);
+            return None;
+        }
+        self.velocity = 0.0;
+        let index = self.item_at(point)?;
+        self.set_selected(Some(index));
+        Some(WidgetEvent::Selected(index))
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        if self.state.rect != rect {
+            self.state.rect = rect;
+            self.state.dirty = true;
+            self.set_offset(self.offset);
+        }
+    }
+
+    fn animate(&mut self, now: Instant) {
+        let last = self.last_animate.replace(now).unwrap_or(now);
+        if self.drag.is_some() || self.velocity == 0.0 {
+            return;
+        }
+
+        let dt = (now - last).as_secs_f32();
+        self.move_to(self.offset + self.velocity * dt);
+        self.velocity *= (-FRICTION * dt).exp();
+
+        let at_end = if self.velocity > 0.0 {
+            self.offset >= self.max_offset()
+        } else {
+            self.offset <= 0.0
+        };
+        if at_end || self.velocity.abs() < MIN_VELOCITY {
+            self.velocity = 0.0;
+        }
+    }
+
+    fn take_scroll(&mut self) -> i32 {
+        std::mem::take(&mut self.scrolled)
+    }
+
+    fn fixed_rect(&self) -> Option<Rect> {
+        Some(self.indicator_column())
+    }
+}
@@ -0,0 +1,22 @@
+pub mod animation;
+pub mod button;
+pub mod chart;
+pub mod checkbox;
+pub mod dialog;
+pub mod gauge;
+pub mod icon;
+pub mod keyboard;
+pub mod label;
+pub mod layout;
+pub mod list_view;
+pub mod progress_bar;
+pub mod screen;
+pub mod slider;
+pub mod style;
+pub mod tab_bar;
+pub mod text_field;
+pub mod theme;
+pub mod toast;
+pub mod toggle;
+pub mod ui;
+pub mod widget;
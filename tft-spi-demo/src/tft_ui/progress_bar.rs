@@ -0,0 +1,66 @@
+use crate::tft_display::{canvas::Canvas, color::Color, geometry::Rect, tft_display::Result};
+use crate::tft_ui::{
+    style::Style,
+    widget::{Widget, WidgetState},
+};
+
+/// Horizontal bar filled to a fraction, not interactive
+pub struct ProgressBar {
+    state: WidgetState,
+    /// 0 to 1
+    value: f32,
+    /// Fill color instead of the style's accent
+    color: Option<Color>,
+}
+
+impl ProgressBar {
+    pub fn new(rect: Rect, value: f32) -> Self {
+        Self {
+            state: WidgetState::new(rect),
+            value: value.clamp(0.0, 1.0),
+            color: None,
+        }
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Sets the filled fraction, clamped to 0 to 1
+    pub fn set_value(&mut self, value: f32) {
+        let value = value.clamp(0.0, 1.0);
+        if self.value != value {
+            self.value = value;
+            self.state.dirty = true;
+        }
+    }
+}
+
+impl Widget for ProgressBar {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let rect = self.state.rect;
+        let radius = style.corner_radius.min(rect.h / 2);
+        canvas.fill_round_rect(rect, radius, style.surface)?;
+
+        let fill = if self.state.enabled {
+            self.color.unwrap_or(style.accent)
+        } else {
+            style.disabled
+        };
+        let width = (rect.w as f32 * self.value).round() as u16;
+        canvas.fill_round_rect(Rect::new(rect.x, rect.y, width, rect.h), radius, fill)
+    }
+}
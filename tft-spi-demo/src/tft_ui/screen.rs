@@ -0,0 +1,559 @@
+use std::time::{Duration, Instant};
+
+use crate::tft_display::{
+    canvas::Canvas,
+    color::Color,
+    dirty::DirtyRegion,
+    framebuffer::Framebuffer,
+    geometry::{Point, Rect},
+    tft_display::Result,
+    tft_display::TftDisplay,
+};
+use crate::tft_touch::event::TouchEvent;
+use crate::tft_ui::{
+    animation::Easing,
+    style::Style,
+    tab_bar::{Tab, TabBar},
+    theme::Theme,
+    ui::{Ui, WidgetId},
+    widget::WidgetEvent,
+};
+
+/// How long a transition takes unless changed with `set_transition_time`
+const TRANSITION_TIME: Duration = Duration::from_millis(250);
+
+/// How the next screen replaces the current one
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Transition {
+    /// At once
+    #[default]
+    None,
+    /// The next screen pushes the current one out to the left
+    SlideLeft,
+    SlideRight,
+    SlideUp,
+    SlideDown,
+    /// Cross-fade
+    Fade,
+}
+
+struct Screen {
+    name: String,
+    ui: Ui,
+    /// Widget given the display's scroll area while the screen is shown
+    scroll: Option<WidgetId>,
+}
+
+/// Transition in progress, from a snapshot of the screen it started on
+struct Running {
+    transition: Transition,
+    from: Framebuffer,
+    start: Instant,
+}
+
+struct Tabs {
+    ui: Ui,
+    bar: WidgetId,
+    /// Screen shown by each tab
+    screens: Vec<usize>,
+    height: u16,
+}
+
+/// Named screens with a navigation stack, an optional tab bar and animated transitions
+///
+/// Each screen is a `Ui` the size of the content area, which is the display minus the
+/// tab bar at the bottom. Screens and the bar are composited into one framebuffer,
+/// `flush` sends what changed.
+pub struct ScreenManager {
+    frame: Framebuffer,
+    style: Style,
+    screens: Vec<Screen>,
+    /// Shown screens, the last is on top
+    stack: Vec<usize>,
+    tabs: Option<Tabs>,
+    running: Option<Running>,
+    transition_time: Duration,
+    /// Touch went to the tab bar, from Down up to Up
+    bar_touch: bool,
+    /// Screen the touch went to otherwise
+    touch_screen: Option<usize>,
+    dirty: DirtyRegion,
+}
+
+impl ScreenManager {
+    pub fn new(width: u16, height: u16, style: Style) -> Self {
+        let mut manager = Self {
+            frame: Framebuffer::new(width, height, style.background),
+            style,
+            screens: Vec::new(),
+            stack: Vec::new(),
+            tabs: None,
+            running: None,
+            transition_time: TRANSITION_TIME,
+            bar_touch: false,
+            touch_screen: None,
+            dirty: DirtyRegion::new(),
+        };
+        manager.dirty.add(manager.frame.bounds());
+        manager
+    }
+
+    /// Manager for the whole screen of `display` in its current orientation
+    pub fn for_display(display: &TftDisplay, style: Style) -> Self {
+        Self::new(display.width(), display.height(), style)
+    }
+
+    /// Area screens are shown in, above the tab bar
+    pub fn content(&self) -> Rect {
+        let bar = self.tabs.as_ref().map_or(0, |tabs| tabs.height);
+        Rect::from_size(self.frame.width(), self.frame.height().saturating_sub(bar))
+    }
+
+    /// Adds a screen, sized to the content area and styled like the manager
+    ///
+    /// The first screen is shown right away. A screen added under an existing name
+    /// replaces it.
+    pub fn add_screen(&mut self, name: impl Into<String>, mut ui: Ui) {
+        let content = self.content();
+        ui.resize(content.w, content.h);
+        ui.set_style(self.style.clone());
+
+        let name = name.into();
+        match self.find(&name) {
+            Some(index) => {
+                self.screens[index] = Screen {
+                    name,
+                    ui,
+                    scroll: None,
+                };
+                if self.stack.last() == Some(&index) {
+                    self.dirty.add(content);
+                }
+            }
+            None => {
+                self.screens.push(Screen {
+                    name,
+                    ui,
+                    scroll: None,
+                });
+                if self.stack.is_empty() {
+                    self.stack.push(self.screens.len() - 1);
+                    self.dirty.add(content);
+                }
+            }
+        }
+    }
+
+    /// Adds a tab bar of `height` at the bottom, each tab showing the named screen
+    ///
+    /// Tabs naming unknown screens are left out.
+    pub fn set_tab_bar(&mut self, tabs: Vec<(String, Tab)>, height: u16) {
+        let (screens, tabs): (Vec<_>, Vec<_>) = tabs
+            .into_iter()
+            .filter_map(|(name, tab)| Some((self.find(&name)?, tab)))
+            .unzip();
+
+        let (w, h) = (self.frame.width(), self.frame.height());
+        let mut ui = Ui::new(w, height, self.style.clone());
+        let bar = ui.add(TabBar::new(Rect::from_size(w, height), tabs));
+        self.tabs = Some(Tabs {
+            ui,
+            bar,
+            screens,
+            height: height.min(h),
+        });
+        self.resize(w, h);
+    }
+
+    pub fn style(&self) -> &Style {
+        &self.style
+    }
+
+    /// Changes the look of every screen and the tab bar, everything is redrawn
+    pub fn set_style(&mut self, style: Style) {
+        for screen in &mut self.screens {
+            screen.ui.set_style(style.clone());
+        }
+        if let Some(tabs) = &mut self.tabs {
+            tabs.ui.set_style(style.clone());
+        }
+        self.style = style;
+        self.dirty.add(self.frame.bounds());
+    }
+
+    /// Switches to `theme` at runtime, loading its font
+    pub fn set_theme(&mut self, theme: &Theme) -> Result<()> {
+        self.set_style(theme.style()?);
+        Ok(())
+    }
+
+    pub fn set_transition_time(&mut self, time: Duration) {
+        self.transition_time = time;
+    }
+
+    /// Name of the screen on top
+    pub fn current(&self) -> Option<&str> {
+        let index = *self.stack.last()?;
+        Some(&self.screens[index].name)
+    }
+
+    pub fn screen(&self, name: &str) -> Option<&Ui> {
+        self.find(name).map(|index| &self.screens[index].ui)
+    }
+
+    pub fn screen_mut(&mut self, name: &str) -> Option<&mut Ui> {
+        self.find(name).map(|index| &mut self.screens[index].ui)
+    }
+
+    /// Lets the display scroll widget `id` of the named screen, e.g. a full-width list
+    ///
+    /// While the screen is shown and no transition runs, `flush` makes the widget the
+    /// display's scroll area and sends only the rows moved in. Where the display can't
+    /// scroll it, in landscape or when it isn't full width, the widget is redrawn.
+    /// Returns `false` for an unknown screen.
+    pub fn set_scroll_widget(&mut self, name: &str, id: Option<WidgetId>) -> bool {
+        let Some(index) = self.find(name) else {
+            return false;
+        };
+        self.screens[index].scroll = id;
+        true
+    }
+
+    pub fn is_transitioning(&self) -> bool {
+        self.running.is_some()
+    }
+
+    /// Shows the named screen on top of the current one, `false` if there is none
+    pub fn push(&mut self, name: &str, transition: Transition) -> bool {
+        let Some(index) = self.find(name) else {
+            return false;
+        };
+        self.show(transition, |stack| stack.push(index));
+        true
+    }
+
+    /// Goes back to the previous screen, `false` if the current one is the last
+    pub fn pop(&mut self, transition: Transition) -> bool {
+        if self.stack.len() < 2 {
+            return false;
+        }
+        self.show(transition, |stack| {
+            stack.pop();
+        });
+        true
+    }
+
+    /// Shows the named screen instead of the current one, `false` if there is none
+    pub fn replace(&mut self, name: &str, transition: Transition) -> bool {
+        let Some(index) = self.find(name) else {
+            return false;
+        };
+        self.show(transition, |stack| {
+            stack.pop();
+            stack.push(index);
+        });
+        true
+    }
+
+    /// Routes a touch to the tab bar or the current screen
+    ///
+    /// Returns what a widget of the current screen reports, tabs switch screens by
+    /// themselves. Moves and new touches are ignored during transitions, only a lifted
+    /// finger still reaches the widget it went down on, so that one is released. The
+    /// tab bar is blocked while the screen shows a dialog.
+    pub fn handle_touch(&mut self, event: TouchEvent) -> Option<(WidgetId, WidgetEvent)> {
+        let running = self.running.is_some();
+        let content = self.content();
+        match event {
+            TouchEvent::Down(_) if running => {
+                self.bar_touch = false;
+                self.touch_screen = None;
+                return None;
+            }
+            TouchEvent::Down(point) => {
+                // An open dialog takes touches on the tab bar too
+                let top = self.stack.last().map(|&index| &self.screens[index].ui);
+                let modal = top.is_some_and(|ui| ui.is_modal());
+                self.bar_touch = self.tabs.is_some() && !content.contains_point(point) && !modal;
+                self.touch_screen = self.stack.last().copied();
+            }
+            TouchEvent::Move(_) if running => return None,
+            TouchEvent::Move(_) | TouchEvent::Up(_) => {}
+        }
+        if !self.bar_touch {
+            let index = self.touch_screen?;
+            let result = self.screens[index].ui.handle_touch(event);
+            return result.filter(|_| !running);
+        }
+
+        let tabs = self.tabs.as_mut()?;
+        let offset = content.bottom();
+        let point = event.point();
+        let event = event.with_point(Point::new(point.x, point.y - offset));
+        let Some((_, WidgetEvent::Selected(tab))) = tabs.ui.handle_touch(event) else {
+            return None;
+        };
+        if running {
+            return None;
+        }
+
+        let screen = tabs.screens[tab];
+        let current = self.stack.last().copied();
+        if current != Some(screen) {
+            // Slide in the direction of the tab's position
+            let from = current.and_then(|current| tabs.screens.iter().position(|&s| s == current));
+            let transition = match from {
+                Some(from) if from > tab => Transition::SlideRight,
+                _ => Transition::SlideLeft,
+            };
+            self.show(transition, |stack| {
+                stack.clear();
+                stack.push(screen);
+            });
+        }
+        None
+    }
+
+    /// Composites the changed areas, or the next frame of a transition, and returns them
+    pub fn render(&mut self) -> Result<Vec<Rect>> {
+        Ok(self.render_scrolled(None)?.0)
+    }
+
+    /// Renders like `render`, letting the current screen scroll the widget that covers
+    /// `scroll_area`
+    ///
+    /// Returns the changed areas and how many rows the scroll area moved up.
+    fn render_scrolled(&mut self, scroll_area: Option<Rect>) -> Result<(Vec<Rect>, i32)> {
+        let content = self.content();
+        let mut scrolled = 0;
+        let mut moved_in = Vec::new();
+        if let Some(&index) = self.stack.last() {
+            let ui = &mut self.screens[index].ui;
+            let (rects, dy) = ui.render_scrolled(scroll_area)?;
+            let stale = |area: Rect| {
+                self.dirty
+                    .rects()
+                    .iter()
+                    .any(|r| r.intersect(area).is_some())
+            };
+            match scroll_area.filter(|&area| dy != 0 && !stale(area)) {
+                Some(area) => {
+                    // Kept apart so the rows moved in don't merge into the whole area
+                    self.frame.scroll(area, dy);
+                    for &rect in &rects {
+                        self.frame.copy_from(ui.framebuffer(), rect, rect.x, rect.y);
+                    }
+                    moved_in = rects;
+                    scrolled = dy;
+                }
+                None => {
+                    // Whatever the screen scrolled is sent whole
+                    if let Some(area) = scroll_area.filter(|_| dy != 0) {
+                        self.dirty.add(area);
+                    }
+                    for rect in rects {
+                        self.dirty.add(rect);
+                    }
+                }
+            }
+            match &self.running {
+                Some(running) => {
+                    let elapsed = running.start.elapsed().as_secs_f32();
+                    let t = elapsed / self.transition_time.as_secs_f32().max(f32::EPSILON);
+                    if t >= 1.0 {
+                        self.running = None;
+                        self.frame.copy_from(ui.framebuffer(), content, 0, 0);
+                    } else {
+                        self.frame.push_clip(content);
+                        let result = compose(&mut self.frame, running, ui.framebuffer(), t);
+                        self.frame.pop_clip();
+                        result?;
+                    }
+                    self.dirty.add(content);
+                }
+                None => {
+                    for &rect in self.dirty.rects() {
+                        self.frame.copy_from(ui.framebuffer(), rect, rect.x, rect.y);
+                    }
+                }
+            }
+        } else {
+            self.frame.fill_rect(content, self.style.background)?;
+        }
+
+        if let Some(tabs) = &mut self.tabs {
+            let offset = content.bottom();
+            for rect in tabs.ui.render()? {
+                self.dirty.add(rect.translate(0, offset));
+            }
+            let bar = Rect::new(0, offset, self.frame.width(), tabs.height);
+            for rect in self
+                .dirty
+                .rects()
+                .iter()
+                .filter_map(|rect| rect.intersect(bar))
+            {
+                self.frame.copy_from(
+                    tabs.ui.framebuffer(),
+                    rect.translate(0, -offset),
+                    rect.x,
+                    rect.y,
+                );
+            }
+        }
+        let mut rects = self.dirty.take(self.frame.bounds());
+        rects.extend(moved_in);
+        Ok((rects, scrolled))
+    }
+
+    /// Renders and sends the changed areas to the display, after vertical blank if
+    /// the display has a `VSync`
+    ///
+    /// Follows the display's size first, so screens adapt to `set_rotation`. Call it
+    /// often while `is_transitioning`, each call draws one step. The manager owns the
+    /// display's scroll area, it follows the current screen's `set_scroll_widget` and
+    /// is dropped during transitions, which draw over it.
+    pub fn flush(&mut self, display: &mut TftDisplay) -> Result<()> {
+        if (display.width(), display.height()) != (self.frame.width(), self.frame.height()) {
+            self.resize(display.width(), display.height());
+        }
+
+        let area = self.scroll_area();
+        if display.scroll_area() != area {
+            // Dropping the scroll area puts its rows back in place, they are sent again
+            if let Some(old) = display.scroll_area() {
+                display.set_scroll_area(None)?;
+                self.dirty.add(old);
+            }
+            // Nothing to do where the display can't scroll it, it is redrawn then
+            if area.is_some() {
+                display.set_scroll_area(area)?;
+            }
+        }
+
+        let (rects, scrolled) = self.render_scrolled(display.scroll_area())?;
+        if !rects.is_empty() || scrolled != 0 {
+            display.wait_for_vblank()?;
+        }
+        if scrolled != 0 {
+            display.scroll_by(scrolled)?;
+        }
+        for rect in rects {
+            display.flush_rect(&self.frame, rect)?;
+        }
+        Ok(())
+    }
+
+    pub fn framebuffer(&self) -> &Framebuffer {
+        &self.frame
+    }
+
+    /// Resizes the framebuffer, the screens and the tab bar, e.g. after rotation
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.frame = Framebuffer::new(width, height, self.style.background);
+        self.running = None;
+        let content = self.content();
+        for screen in &mut self.screens {
+            screen.ui.resize(content.w, content.h);
+        }
+        if let Some(tabs) = &mut self.tabs {
+            tabs.height = tabs.height.min(height);
+            tabs.ui.resize(width, tabs.height);
+            let bar = Rect::from_size(width, tabs.height);
+            if let Some(widget) = tabs.ui.widget_mut(tabs.bar) {
+                widget.set_rect(bar);
+            }
+        }
+        self.dirty.add(self.frame.bounds());
+    }
+
+    /// Area of the current screen's scroll widget, `None` during transitions
+    fn scroll_area(&self) -> Option<Rect> {
+        if self.running.is_some() {
+            return None;
+        }
+        let screen = &self.screens[*self.stack.last()?];
+        let widget = screen.ui.widget(screen.scroll?)?;
+        widget.is_visible().then(|| widget.rect())
+    }
+
+    fn find(&self, name: &str) -> Option<usize> {
+        self.screens.iter().position(|screen| screen.name == name)
+    }
+
+    /// Changes the stack, starting `transition` from what is shown now
+    fn show(&mut self, transition: Transition, change: impl FnOnce(&mut Vec<usize>)) {
+        let content = self.content();
+        let from = self.stack.last().copied();
+        change(&mut self.stack);
+        let Some(&to) = self.stack.last() else {
+            return;
+        };
+        if from == Some(to) {
+            return;
+        }
+
+        self.screens[to].ui.invalidate(content);
+        self.dirty.add(content);
+        self.running = match (transition, from) {
+            (Transition::None, _) | (_, None) => None,
+            (transition, Some(_)) => {
+                // The snapshot is the composited frame, so the outgoing screen can
+                // still be mid-transition itself
+                let mut snapshot = Framebuffer::new(content.w, content.h, Color::BLACK);
+                snapshot.copy_from(&self.frame, content, 0, 0);
+                Some(Running {
+                    transition,
+                    from: snapshot,
+                    start: Instant::now(),
+                })
+            }
+        };
+
+        if let Some(tabs) = &mut self.tabs {
+            if let Some(tab) = tabs.screens.iter().position(|&screen| screen == to) {
+                if let Some(bar) = tabs.ui.get_mut::<TabBar>(tabs.bar) {
+                    bar.set_selected(tab);
+                }
+            }
+        }
+    }
+}
+
+/// Draws step `t` (0 to 1) of a transition into the content area at the top left
+fn compose(frame: &mut Framebuffer, running: &Running, to: &Framebuffer, t: f32) -> Result<()> {
+    // Fast at first and settling at the end
+    let eased = Easing::EaseOut.apply(t);
+    let from = &running.from;
+    let (w, h) = (from.width(), from.height());
+    let dx = (w as f32 * eased).round() as i32;
+    let dy = (h as f32 * eased).round() as i32;
+    let all = from.bounds();
+
+    match running.transition {
+        Transition::None => frame.copy_from(to, all, 0, 0),
+        Transition::SlideLeft => {
+            frame.copy_from(from, all, -dx, 0);
+            frame.copy_from(to, all, w as i32 - dx, 0);
+        }
+        Transition::SlideRight => {
+            frame.copy_from(from, all, dx, 0);
+            frame.copy_from(to, all, dx - w as i32, 0);
+        }
+        Transition::SlideUp => {
+            frame.copy_from(from, all, 0, -dy);
+            frame.copy_from(to, all, 0, h as i32 - dy);
+        }
+        Transition::SlideDown => {
+            frame.copy_from(from, all, 0, dy);
+            frame.copy_from(to, all, 0, dy - h as i32);
+        }
+        Transition::Fade => {
+            frame.draw_rows(all, &mut |y, out| {
+                let (from, to) = (from.row(0, y, w), to.row(0, y, w));
+                out.extend(from.iter().zip(to).map(|(a, b)| a.lerp(*b, eased)));
+            })?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use crate::tft_display::{canvas::Canvas, geometry::Rect, tft_display::Result};
+use crate::tft_touch::event::TouchEvent;
+use crate::tft_ui::{
+    style::Style,
+    widget::{Widget, WidgetEvent, WidgetState},
+};
+
+/// Height of the track in pixels
+const TRACK_HEIGHT: u16 = 6;
+
+/// Horizontal slider, reports `Changed` while dragged
+pub struct Slider {
+    state: WidgetState,
+    min: f32,
+    max: f32,
+    value: f32,
+    /// Values snap to multiples of this above `min`
+    step: Option<f32>,
+}
+
+impl Slider {
+    pub fn new(rect: Rect, min: f32, max: f32, value: f32) -> Self {
+        let (min, max) = if min <= max { (min, max) } else { (max, min) };
+        Self {
+            state: WidgetState::new(rect),
+            min,
+            max,
+            value: value.clamp(min, max),
+            step: None,
+        }
+    }
+
+    pub fn with_step(mut self, step: f32) -> Self {
+        self.step = (step > 0.0).then_some(step);
+        self.value = self.snap(self.value);
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn range(&self) -> (f32, f32) {
+        (self.min, self.max)
+    }
+
+    pub fn set_value(&mut self, value: f32) {
+        let value = self.snap(value);
+        if self.value != value {
+            self.value = value;
+            self.state.dirty = true;
+        }
+    }
+
+    /// Position of the value along the track, 0 to 1
+    fn fraction(&self) -> f32 {
+        if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        }
+    }
+
+    fn snap(&self, value: f32) -> f32 {
+        let value = match self.step {
+            Some(step) => self.min + ((value - self.min) / step).round() * step,
+            None => value,
+        };
+        value.clamp(self.min, self.max)
+    }
+
+    /// The knob's radius, its center travels `radius` inside either end
+    fn radius(&self) -> f32 {
+        (self.state.rect.h as f32 / 2.0 - 1.0).max(1.0)
+    }
+}
+
+impl Widget for Slider {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let rect = self.state.rect;
+        let radius = self.radius();
+        let left = rect.x as f32 - 0.5 + radius;
+        let travel = (rect.w as f32 - 2.0 * radius).max(0.0);
+        let knob_x = left + travel * self.fraction();
+        let cy = rect.y as f32 + rect.h as f32 / 2.0 - 0.5;
+
+        let height = TRACK_HEIGHT.min(rect.h);
+        let track = Rect::new(
+            rect.x,
+            rect.y + (rect.h - height) as i32 / 2,
+            rect.w,
+            height,
+        );
+        canvas.fill_round_rect(track, height / 2, style.surface)?;
+
+        let (fill, knob) = match (self.state.enabled, self.state.pressed) {
+            (false, _) => (style.disabled, style.disabled),
+            (true, true) => (style.accent, style.pressed),
+            (true, false) => (style.accent, style.foreground),
+        };
+        let filled = Rect::new(
+            track.x,
+            track.y,
+            (knob_x.round() as i32 - track.x).max(0) as u16,
+            height,
+        );
+        canvas.fill_round_rect(filled, height / 2, fill)?;
+        canvas.fill_circle_aa(knob_x, cy, radius, knob)
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn touch(&mut self, event: TouchEvent) -> Option<WidgetEvent> {
+        let pressed = !matches!(event, TouchEvent::Up(_));
+        if self.state.pressed != pressed {
+            self.state.pressed = pressed;
+            self.state.dirty = true;
+        }
+        if !pressed {
+            return None;
+        }
+
+        // Keeps following the finger outside the rectangle, clamped to the ends
+        let rect = self.state.rect;
+        let radius = self.radius();
+        let travel = rect.w as f32 - 2.0 * radius;
+        let fraction = if travel > 0.0 {
+            (event.point().x as f32 - rect.x as f32 - radius + 0.5) / travel
+        } else {
+            0.0
+        };
+        let before = self.value;
+        self.set_value(self.min + fraction.clamp(0.0, 1.0) * (self.max - self.min));
+        (self.value != before).then_some(WidgetEvent::Changed(self.value))
+    }
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use crate::tft_display::{color::Color, font::Font};
+use crate::tft_ui::theme::Theme;
+
+/// Colors, font and metrics widgets draw with, usually built from a `Theme`
+#[derive(Clone)]
+pub struct Style {
+    /// Behind the widgets
+    pub background: Color,
+    /// Faces of buttons, tracks of sliders and bars
+    pub surface: Color,
+    /// Text and icons
+    pub foreground: Color,
+    /// Active parts: toggles that are on, slider values, progress
+    pub accent: Color,
+    /// Faces of pressed widgets
+    pub pressed: Color,
+    /// Text and outlines of disabled widgets
+    pub disabled: Color,
+    pub font: Arc<dyn Font>,
+    pub corner_radius: u16,
+    /// Space between a widget's edge and its content
+    pub padding: u16,
+}
+
+/// The dark theme
+impl Default for Style {
+    fn default() -> Self {
+        Theme::dark()
+            .style()
+            .expect("the dark theme uses the built-in font, which always loads")
+    }
+}
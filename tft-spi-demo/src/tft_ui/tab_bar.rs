@@ -0,0 +1,144 @@
+use crate::tft_display::{
+    canvas::Canvas, color::Color, enums::Align, geometry::Rect, tft_display::Result,
+};
+use crate::tft_touch::event::TouchEvent;
+use crate::tft_ui::{
+    style::Style,
+    widget::{self, Widget, WidgetEvent, WidgetState},
+};
+
+/// Height of the line marking the selected tab
+const INDICATOR_HEIGHT: u16 = 4;
+
+/// One entry of a `TabBar`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tab {
+    pub label: String,
+    /// Face and text colors instead of the style's
+    pub colors: Option<(Color, Color)>,
+}
+
+impl Tab {
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            colors: None,
+        }
+    }
+
+    pub fn with_colors(mut self, face: Color, text: Color) -> Self {
+        self.colors = Some((face, text));
+        self
+    }
+}
+
+/// Row of equally wide tabs, one of them selected, reports `Selected`
+pub struct TabBar {
+    state: WidgetState,
+    tabs: Vec<Tab>,
+    selected: usize,
+    /// Tab under the finger while pressed
+    pressed: Option<usize>,
+}
+
+impl TabBar {
+    pub fn new(rect: Rect, tabs: Vec<Tab>) -> Self {
+        Self {
+            state: WidgetState::new(rect),
+            tabs,
+            selected: 0,
+            pressed: None,
+        }
+    }
+
+    pub fn tabs(&self) -> &[Tab] {
+        &self.tabs
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn set_selected(&mut self, index: usize) {
+        if self.selected != index && index < self.tabs.len() {
+            self.selected = index;
+            self.state.dirty = true;
+        }
+    }
+
+    fn tab_rect(&self, index: usize) -> Rect {
+        let rect = self.state.rect;
+        let count = self.tabs.len().max(1) as u32;
+        let from = rect.w as u32 * index as u32 / count;
+        let to = rect.w as u32 * (index as u32 + 1) / count;
+        Rect::new(rect.x + from as i32, rect.y, (to - from) as u16, rect.h)
+    }
+
+    fn tab_at(&self, x: i32) -> Option<usize> {
+        (0..self.tabs.len()).find(|&index| {
+            let tab = self.tab_rect(index);
+            x >= tab.x && x < tab.right()
+        })
+    }
+}
+
+impl Widget for TabBar {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        for (index, tab) in self.tabs.iter().enumerate() {
+            let rect = self.tab_rect(index);
+            let (face, text) = tab.colors.unwrap_or((style.surface, style.foreground));
+            let (face, text) = match (self.state.enabled, self.pressed == Some(index)) {
+                (false, _) => (style.surface, style.disabled),
+                (true, true) => (style.pressed, text),
+                (true, false) => (face, text),
+            };
+            canvas.fill_rect(rect, face)?;
+
+            if index == self.selected {
+                let bottom = rect.bottom() - INDICATOR_HEIGHT as i32;
+                canvas.fill_rect(Rect::new(rect.x, bottom, rect.w, INDICATOR_HEIGHT), text)?;
+            }
+            let label = rect.inset(style.padding);
+            widget::draw_text_block(canvas, style, &tab.label, label, Align::Center, text)?;
+        }
+        Ok(())
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn touch(&mut self, event: TouchEvent) -> Option<WidgetEvent> {
+        self.state.track_press(event);
+        let point = event.point();
+        let under = if self.state.rect.contains_point(point) {
+            self.tab_at(point.x)
+        } else {
+            None
+        };
+
+        let pressed = match event {
+            TouchEvent::Up(_) => None,
+            _ => under,
+        };
+        if self.pressed != pressed {
+            self.pressed = pressed;
+            self.state.dirty = true;
+        }
+
+        let TouchEvent::Up(_) = event else {
+            return None;
+        };
+        let index = under?;
+        self.set_selected(index);
+        Some(WidgetEvent::Selected(index))
+    }
+}
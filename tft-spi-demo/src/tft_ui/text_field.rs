@@ -0,0 +1,193 @@
+use crate::tft_display::{canvas::Canvas, font::text, geometry::Rect, tft_display::Result};
+use crate::tft_touch::event::TouchEvent;
+use crate::tft_ui::{
+    keyboard::KeyInput,
+    style::Style,
+    widget::{Widget, WidgetEvent, WidgetState},
+};
+
+/// Width of the text cursor in pixels
+const CURSOR_WIDTH: u16 = 2;
+
+/// Single line text input with a cursor, edited with `apply`
+///
+/// Tapping it reports `Clicked`, usually to focus it and show a `Keyboard`. Text wider
+/// than the field scrolls to keep the cursor visible.
+pub struct TextField {
+    state: WidgetState,
+    text: String,
+    /// Byte offset in `text`, always on a character boundary
+    cursor: usize,
+    placeholder: String,
+    /// Shown as `*`, e.g. for PINs and passwords
+    masked: bool,
+    max_chars: Option<usize>,
+    focused: bool,
+}
+
+impl TextField {
+    pub fn new(rect: Rect, text: impl Into<String>) -> Self {
+        let text = text.into();
+        Self {
+            state: WidgetState::new(rect),
+            cursor: text.len(),
+            text,
+            placeholder: String::new(),
+            masked: false,
+            max_chars: None,
+            focused: false,
+        }
+    }
+
+    /// Gray hint shown while the field is empty
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    pub fn masked(mut self) -> Self {
+        self.masked = true;
+        self
+    }
+
+    pub fn with_max_chars(mut self, max_chars: usize) -> Self {
+        self.max_chars = Some(max_chars);
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Replaces the text, the cursor goes to the end
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+        self.cursor = self.text.len();
+        self.state.dirty = true;
+    }
+
+    /// Cursor position in characters
+    pub fn cursor(&self) -> usize {
+        self.text[..self.cursor].chars().count()
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Only a focused field shows its cursor
+    pub fn set_focused(&mut self, focused: bool) {
+        if self.focused != focused {
+            self.focused = focused;
+            self.state.dirty = true;
+        }
+    }
+
+    /// Edits the text with a key from a `Keyboard`, `true` if anything changed
+    ///
+    /// `Enter` is left to the caller, e.g. to submit the text.
+    pub fn apply(&mut self, input: KeyInput) -> bool {
+        let before = self.text[..self.cursor].chars().next_back();
+        let after = self.text[self.cursor..].chars().next();
+        match input {
+            KeyInput::Char(c) => {
+                let full = self
+                    .max_chars
+                    .is_some_and(|max| self.text.chars().count() >= max);
+                if full || c.is_control() {
+                    return false;
+                }
+                self.text.insert(self.cursor, c);
+                self.cursor += c.len_utf8();
+            }
+            KeyInput::Backspace => {
+                let Some(c) = before else {
+                    return false;
+                };
+                self.cursor -= c.len_utf8();
+                self.text.remove(self.cursor);
+            }
+            KeyInput::Left => {
+                let Some(c) = before else {
+                    return false;
+                };
+                self.cursor -= c.len_utf8();
+            }
+            KeyInput::Right => {
+                let Some(c) = after else {
+                    return false;
+                };
+                self.cursor += c.len_utf8();
+            }
+            KeyInput::Enter => return false,
+        }
+        self.state.dirty = true;
+        true
+    }
+
+    /// The text as drawn, with the cursor's byte offset in it
+    fn shown(&self) -> (String, usize) {
+        if !self.masked {
+            return (self.text.clone(), self.cursor);
+        }
+        let cursor = self.cursor();
+        ("*".repeat(self.text.chars().count()), cursor)
+    }
+}
+
+impl Widget for TextField {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let rect = self.state.rect;
+        canvas.fill_round_rect(rect, style.corner_radius, style.surface)?;
+        if self.focused {
+            canvas.draw_rect(rect, style.accent)?;
+        }
+
+        let font = style.font.as_ref();
+        let metrics = font.line_metrics();
+        let inner = rect.inset(style.padding);
+        let top = inner.y + (inner.h as i32 - metrics.line_height).max(0) / 2;
+        let baseline = top + metrics.ascent;
+
+        // Scrolled so the cursor stays inside, text starting at the left edge if it fits
+        let (shown, cursor) = self.shown();
+        let cursor_x = text::line_width(font, &shown[..cursor]);
+        let room = inner.w.saturating_sub(CURSOR_WIDTH) as f32;
+        let x = inner.x as f32 - (cursor_x - room).max(0.0);
+
+        let (line, color) = match (shown.is_empty(), self.state.enabled) {
+            (true, _) => (self.placeholder.as_str(), style.disabled),
+            (false, true) => (shown.as_str(), style.foreground),
+            (false, false) => (shown.as_str(), style.disabled),
+        };
+        canvas.push_clip(inner);
+        let result = canvas.draw_text_line(font, line, x, baseline, color, None);
+        canvas.pop_clip();
+        result?;
+
+        if !self.focused {
+            return Ok(());
+        }
+        let height = metrics.line_height as u16;
+        let cursor = Rect::new((x + cursor_x).round() as i32, top, CURSOR_WIDTH, height);
+        canvas.fill_rect(cursor, style.accent)
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn touch(&mut self, event: TouchEvent) -> Option<WidgetEvent> {
+        self.state
+            .track_press(event)
+            .then_some(WidgetEvent::Clicked)
+    }
+}
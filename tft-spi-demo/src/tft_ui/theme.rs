@@ -0,0 +1,189 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::tft_display::{
+    color::Color,
+    error::Error,
+    font::{bitmap::BitmapFont, truetype::FontFace, Font},
+    tft_display::Result,
+};
+use crate::tft_ui::style::Style;
+
+/// Which font a theme draws text with
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum FontChoice {
+    /// The 5x7 font, each pixel drawn `scale` x `scale`
+    Builtin { scale: u8 },
+    /// BDF or PCF file, each pixel drawn `scale` x `scale`
+    Bitmap {
+        path: PathBuf,
+        #[serde(default = "one")]
+        scale: u8,
+    },
+    /// TrueType or OpenType file at `size` pixels per em
+    TrueType { path: PathBuf, size: f32 },
+}
+
+impl FontChoice {
+    pub fn load(&self) -> Result<Arc<dyn Font>> {
+        Ok(match self {
+            Self::Builtin { scale } => Arc::new(BitmapFont::builtin().scaled(*scale)),
+            Self::Bitmap { path, scale } => Arc::new(BitmapFont::open(path)?.scaled(*scale)),
+            Self::TrueType { path, size } => Arc::new(FontFace::open(path)?.sized(*size)),
+        })
+    }
+
+    /// Font path relative to `dir`, unless it is absolute
+    fn resolve(&mut self, dir: &Path) {
+        match self {
+            Self::Builtin { .. } => {}
+            Self::Bitmap { path, .. } | Self::TrueType { path, .. } => *path = dir.join(&*path),
+        }
+    }
+}
+
+/// Description of how the UI looks, e.g. for one customer's branding
+///
+/// Themes load from TOML or JSON files. Colors are written as `#RRGGBB`, `#RGB` or a
+/// CSS color name, everything left out is taken from the dark preset:
+///
+/// ```toml
+/// accent = "#E4002B"
+/// corner_radius = 0
+/// font = { kind = "truetype", path = "Brand-Regular.ttf", size = 18.0 }
+/// ```
+///
+/// `style` turns it into the `Style` widgets draw with.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    #[serde(with = "color")]
+    pub background: Color,
+    #[serde(with = "color")]
+    pub surface: Color,
+    #[serde(with = "color")]
+    pub foreground: Color,
+    #[serde(with = "color")]
+    pub accent: Color,
+    #[serde(with = "color")]
+    pub pressed: Color,
+    #[serde(with = "color")]
+    pub disabled: Color,
+    pub font: FontChoice,
+    pub corner_radius: u16,
+    pub padding: u16,
+}
+
+impl Theme {
+    /// Light text on black
+    pub fn dark() -> Self {
+        Self {
+            background: Color::BLACK,
+            surface: Color::from_eight_bit_rgb(0x40, 0x40, 0x48),
+            foreground: Color::WHITE,
+            accent: Color::from_eight_bit_rgb(0x20, 0x90, 0xF0),
+            pressed: Color::from_eight_bit_rgb(0x70, 0x70, 0x80),
+            disabled: Color::from_eight_bit_rgb(0x80, 0x80, 0x80),
+            font: FontChoice::Builtin { scale: 2 },
+            corner_radius: 6,
+            padding: 6,
+        }
+    }
+
+    /// Dark text on white
+    pub fn light() -> Self {
+        Self {
+            background: Color::WHITE,
+            surface: Color::from_eight_bit_rgb(0xD8, 0xD8, 0xE0),
+            foreground: Color::BLACK,
+            accent: Color::from_eight_bit_rgb(0x00, 0x6C, 0xD0),
+            pressed: Color::from_eight_bit_rgb(0xA8, 0xA8, 0xB4),
+            disabled: Color::from_eight_bit_rgb(0x90, 0x90, 0x90),
+            ..Self::dark()
+        }
+    }
+
+    /// Reads a `.toml` or `.json` theme file
+    ///
+    /// Font paths are relative to the theme file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        let mut theme = match extension.map(str::to_ascii_lowercase).as_deref() {
+            Some("toml") => Self::from_toml(&text)?,
+            Some("json") => Self::from_json(&text)?,
+            _ => {
+                return Err(Error::Decode(format!(
+                    "Unknown theme format: {}",
+                    path.display()
+                )))
+            }
+        };
+
+        if let Some(dir) = path.parent() {
+            theme.font.resolve(dir);
+        }
+        Ok(theme)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self> {
+        Ok(serde_json::from_str(text)?)
+    }
+
+    /// Loads the font and builds the style widgets draw with
+    pub fn style(&self) -> Result<Style> {
+        Ok(Style {
+            background: self.background,
+            surface: self.surface,
+            foreground: self.foreground,
+            accent: self.accent,
+            pressed: self.pressed,
+            disabled: self.disabled,
+            font: self.font.load()?,
+            corner_radius: self.corner_radius,
+            padding: self.padding,
+        })
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+fn one() -> u8 {
+    1
+}
+
+/// Colors as `#RRGGBB` strings, read back from hex or CSS color names
+mod color {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        color: &Color,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("#{:06X}", color.to_24_bit()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Color, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Color::from_hex(&text)
+            .or_else(|_| Color::from_name(&text))
+            .map_err(de::Error::custom)
+    }
+}
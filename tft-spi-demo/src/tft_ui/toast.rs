@@ -0,0 +1,75 @@
+use crate::tft_display::{canvas::Canvas, enums::Align, geometry::Rect, tft_display::Result};
+use crate::tft_touch::event::TouchEvent;
+use crate::tft_ui::{
+    style::Style,
+    widget::{self, Widget, WidgetEvent, WidgetState},
+};
+
+/// Short notification, reports `Clicked` when tapped away
+///
+/// Hidden until opened with `Ui::open_toast`, which shows it at the bottom above
+/// everything else and closes it after a while.
+pub struct Toast {
+    state: WidgetState,
+    text: String,
+}
+
+impl Toast {
+    pub fn new(rect: Rect, text: impl Into<String>) -> Self {
+        let mut state = WidgetState::new(rect);
+        state.visible = false;
+        Self {
+            state,
+            text: text.into(),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        if self.text != text {
+            self.text = text;
+            self.state.dirty = true;
+        }
+    }
+}
+
+impl Widget for Toast {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        // Inverted colors stand out from whatever is underneath
+        let rect = self.state.rect;
+        canvas.fill_round_rect(rect, rect.h / 2, style.foreground)?;
+        let content = rect.inset(style.padding);
+        widget::draw_text_block(
+            canvas,
+            style,
+            &self.text,
+            content,
+            Align::Center,
+            style.background,
+        )
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn touch(&mut self, event: TouchEvent) -> Option<WidgetEvent> {
+        if !self.state.track_press(event) {
+            return None;
+        }
+        self.set_visible(false);
+        Some(WidgetEvent::Clicked)
+    }
+}
@@ -0,0 +1,79 @@
+use crate::tft_display::{canvas::Canvas, geometry::Rect, tft_display::Result};
+use crate::tft_touch::event::TouchEvent;
+use crate::tft_ui::{
+    style::Style,
+    widget::{Widget, WidgetEvent, WidgetState},
+};
+
+/// On/off switch drawn as a pill with a sliding knob, reports `Toggled`
+pub struct Toggle {
+    state: WidgetState,
+    on: bool,
+}
+
+impl Toggle {
+    pub fn new(rect: Rect, on: bool) -> Self {
+        Self {
+            state: WidgetState::new(rect),
+            on,
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    pub fn set_on(&mut self, on: bool) {
+        if self.on != on {
+            self.on = on;
+            self.state.dirty = true;
+        }
+    }
+}
+
+impl Widget for Toggle {
+    fn state(&self) -> &WidgetState {
+        &self.state
+    }
+
+    fn state_mut(&mut self) -> &mut WidgetState {
+        &mut self.state
+    }
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()> {
+        let rect = self.state.rect;
+        let track = match (self.state.enabled, self.on) {
+            (true, true) => style.accent,
+            (true, false) if self.state.pressed => style.pressed,
+            _ => style.surface,
+        };
+        let knob = if self.state.enabled {
+            style.foreground
+        } else {
+            style.disabled
+        };
+        canvas.fill_round_rect(rect, rect.h / 2, track)?;
+
+        // Pixel centers are at integer coordinates
+        let radius = (rect.h as f32 / 2.0 - 3.0).max(1.0);
+        let cy = rect.y as f32 + rect.h as f32 / 2.0 - 0.5;
+        let cx = if self.on {
+            rect.right() as f32 - rect.h as f32 / 2.0 - 0.5
+        } else {
+            rect.x as f32 + rect.h as f32 / 2.0 - 0.5
+        };
+        canvas.fill_circle_aa(cx, cy, radius, knob)
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    fn touch(&mut self, event: TouchEvent) -> Option<WidgetEvent> {
+        if !self.state.track_press(event) {
+            return None;
+        }
+        self.set_on(!self.on);
+        Some(WidgetEvent::Toggled(self.on))
+    }
+}
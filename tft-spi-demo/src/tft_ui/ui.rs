@@ -0,0 +1,583 @@
+use std::time::{Duration, Instant};
+
+use crate::tft_display::{
+    canvas::Canvas, dirty::DirtyRegion, framebuffer::Framebuffer, geometry::Rect,
+    tft_display::Result, tft_display::TftDisplay,
+};
+use crate::tft_touch::event::TouchEvent;
+use crate::tft_ui::{
+    animation::{Easing, Interpolate, Tween},
+    layout::Layout,
+    style::Style,
+    theme::Theme,
+    widget::{Widget, WidgetEvent},
+};
+
+/// Space between a toast and the bottom edge
+const TOAST_MARGIN: u16 = 16;
+
+/// Handle of a widget added to a `Ui`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WidgetId(usize);
+
+/// Widget opened above all others, e.g. a dialog or a toast
+struct Overlay {
+    index: usize,
+    /// Takes every touch while open, centered instead of at the bottom
+    modal: bool,
+    /// Closes by itself at this time
+    until: Option<Instant>,
+    /// Where the overlay was opened
+    area: Rect,
+    /// Pixels underneath, kept current while open and put back on closing
+    under: Framebuffer,
+}
+
+/// Sets a widget's property for a point in time, `false` once finished
+type Step = Box<dyn FnMut(&mut dyn Widget, Instant) -> bool>;
+
+/// Property change of a widget played over time, see `Ui::animate`
+struct Animation {
+    index: usize,
+    step: Step,
+}
+
+/// Retained widgets composited in a framebuffer
+///
+/// Widgets are drawn in the order they were added, later ones on top. Changed widgets
+/// mark where they were and where they are now, `flush` redraws just those areas and
+/// sends them to the display. Dialogs and toasts are opened above everything else.
+pub struct Ui {
+    frame: Framebuffer,
+    style: Style,
+    widgets: Vec<Box<dyn Widget>>,
+    /// Where each widget was last drawn, `None` while hidden
+    drawn: Vec<Option<Rect>>,
+    dirty: DirtyRegion,
+    /// Widget receiving the current touch, from Down up to Up
+    captured: Option<usize>,
+    /// Places the widgets whenever the size changes
+    layout: Option<Layout>,
+    /// Open overlays, the last on top
+    overlays: Vec<Overlay>,
+    animations: Vec<Animation>,
+}
+
+impl Ui {
+    pub fn new(width: u16, height: u16, style: Style) -> Self {
+        let mut ui = Self {
+            frame: Framebuffer::new(width, height, style.background),
+            style,
+            widgets: Vec::new(),
+            drawn: Vec::new(),
+            dirty: DirtyRegion::new(),
+            captured: None,
+            layout: None,
+            overlays: Vec::new(),
+            animations: Vec::new(),
+        };
+        ui.invalidate(ui.frame.bounds());
+        ui
+    }
+
+    /// UI for the whole screen of `display` in its current orientation
+    pub fn for_display(display: &TftDisplay, style: Style) -> Self {
+        Self::new(display.width(), display.height(), style)
+    }
+
+    pub fn style(&self) -> &Style {
+        &self.style
+    }
+
+    /// Changes the look of every widget, the whole UI is redrawn
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+        self.invalidate(self.frame.bounds());
+    }
+
+    /// Switches to `theme` at runtime, loading its font
+    pub fn set_theme(&mut self, theme: &Theme) -> Result<()> {
+        self.set_style(theme.style()?);
+        Ok(())
+    }
+
+    /// Resizes the framebuffer and lays the widgets out again
+    ///
+    /// `flush` does this by itself when the display was rotated.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.frame = Framebuffer::new(width, height, self.style.background);
+        self.invalidate(self.frame.bounds());
+        self.relayout();
+
+        // Everything is drawn again, which saves what's under the overlays
+        for index in 0..self.overlays.len() {
+            let overlay = &self.overlays[index];
+            let widget = &self.widgets[overlay.index];
+            let area = self.place(widget.rect(), overlay.modal);
+            self.widgets[self.overlays[index].index].set_rect(area);
+            let overlay = &mut self.overlays[index];
+            overlay.area = area;
+            overlay.under = Framebuffer::new(area.w, area.h, self.style.background);
+        }
+    }
+
+    /// Places the widgets with `layout` now and whenever the UI is resized
+    ///
+    /// Widgets not in the layout keep their rectangles.
+    pub fn set_layout(&mut self, layout: Layout) {
+        self.layout = Some(layout);
+        self.relayout();
+    }
+
+    pub fn layout(&self) -> Option<&Layout> {
+        self.layout.as_ref()
+    }
+
+    /// Applies the layout to the whole framebuffer again
+    pub fn relayout(&mut self) {
+        let Some(layout) = &self.layout else {
+            return;
+        };
+        for (id, rect) in layout.rects(self.frame.bounds()) {
+            if let Some(widget) = self.widgets.get_mut(id.0) {
+                widget.set_rect(rect);
+            }
+        }
+    }
+
+    pub fn add(&mut self, widget: impl Widget) -> WidgetId {
+        self.dirty.add(widget.rect());
+        self.widgets.push(Box::new(widget));
+        self.drawn.push(None);
+        WidgetId(self.widgets.len() - 1)
+    }
+
+    pub fn widget(&self, id: WidgetId) -> Option<&dyn Widget> {
+        self.widgets.get(id.0).map(Box::as_ref)
+    }
+
+    pub fn widget_mut(&mut self, id: WidgetId) -> Option<&mut dyn Widget> {
+        self.widgets.get_mut(id.0).map(Box::as_mut)
+    }
+
+    /// The widget as its concrete type, `None` if it is another type
+    pub fn get<W: Widget>(&self, id: WidgetId) -> Option<&W> {
+        let widget: &dyn std::any::Any = self.widgets.get(id.0)?.as_ref();
+        widget.downcast_ref()
+    }
+
+    pub fn get_mut<W: Widget>(&mut self, id: WidgetId) -> Option<&mut W> {
+        let widget: &mut dyn std::any::Any = self.widgets.get_mut(id.0)?.as_mut();
+        widget.downcast_mut()
+    }
+
+    /// Plays `tween` on a property of the widget, `apply` sets each value
+    ///
+    /// Steps run before every render until the tween is done, so changes from all
+    /// animations go out together in the next flush. For example
+    /// `ui.animate(id, tween, |label: &mut Label, color| label.set_color(Some(color)))`.
+    pub fn animate<W, T>(
+        &mut self,
+        id: WidgetId,
+        tween: Tween<T>,
+        mut apply: impl FnMut(&mut W, T) + 'static,
+    ) where
+        W: Widget,
+        T: Interpolate + 'static,
+    {
+        self.schedule(id, move |widget, now| {
+            let widget: &mut dyn std::any::Any = widget;
+            if let Some(widget) = widget.downcast_mut() {
+                apply(widget, tween.value_at(now));
+            }
+            !tween.is_done(now)
+        });
+    }
+
+    /// Moves and resizes the widget to `rect` over `duration`
+    pub fn animate_rect(&mut self, id: WidgetId, rect: Rect, duration: Duration, easing: Easing) {
+        let Some(widget) = self.widgets.get(id.0) else {
+            return;
+        };
+        let tween = Tween::new(widget.rect(), rect, duration).with_easing(easing);
+        self.schedule(id, move |widget, now| {
+            widget.set_rect(tween.value_at(now));
+            !tween.is_done(now)
+        });
+    }
+
+    /// Stops the widget's animations where they are
+    pub fn stop_animations(&mut self, id: WidgetId) {
+        self.animations.retain(|animation| animation.index != id.0);
+    }
+
+    pub fn is_animating(&self) -> bool {
+        !self.animations.is_empty()
+    }
+
+    /// Shows the widget centered above all others until it hides itself or is closed
+    ///
+    /// While open it takes every touch, e.g. a `Dialog`. The pixels underneath are
+    /// kept and put back on closing.
+    pub fn open_dialog(&mut self, id: WidgetId) {
+        self.open(id, true, None);
+    }
+
+    /// Shows the widget at the bottom above all others for `duration`, e.g. a `Toast`
+    ///
+    /// Touch outside it goes to the widgets underneath.
+    pub fn open_toast(&mut self, id: WidgetId, duration: Duration) {
+        self.open(id, false, Some(Instant::now() + duration));
+    }
+
+    /// Closes a dialog or toast, putting back what was underneath on the next render
+    pub fn close(&mut self, id: WidgetId) {
+        if let Some(widget) = self.widgets.get_mut(id.0) {
+            if self.overlays.iter().any(|overlay| overlay.index == id.0) {
+                widget.set_visible(false);
+            }
+        }
+    }
+
+    pub fn is_open(&self, id: WidgetId) -> bool {
+        let open = |overlay: &Overlay| overlay.index == id.0;
+        self.overlays.iter().any(open) && self.widgets[id.0].is_visible()
+    }
+
+    /// Whether a dialog takes all touch input
+    pub fn is_modal(&self) -> bool {
+        self.overlays.iter().any(|overlay| overlay.modal)
+    }
+
+    /// Routes a touch to the widget under it and returns what that widget reports
+    ///
+    /// The topmost enabled widget under a Down keeps the touch until the Up, even if
+    /// the finger moves off it. An open dialog takes touches anywhere.
+    pub fn handle_touch(&mut self, event: TouchEvent) -> Option<(WidgetId, WidgetEvent)> {
+        if let TouchEvent::Down(point) = event {
+            let overlay = self.overlays.iter().rev().find(|overlay| {
+                let widget = &self.widgets[overlay.index];
+                overlay.modal || widget.is_interactive() && widget.hit_test(point)
+            });
+            self.captured = match overlay {
+                Some(overlay) => Some(overlay.index),
+                None => self.widgets.iter().rposition(|widget| {
+                    widget.is_interactive() && widget.is_enabled() && widget.hit_test(point)
+                }),
+            };
+        }
+
+        let index = self.captured?;
+        if let TouchEvent::Up(_) = event {
+            self.captured = None;
+        }
+        let widget = &mut self.widgets[index];
+        // Disabled or hidden while touched, the touch ends here
+        if !widget.is_enabled() || !widget.is_visible() {
+            self.captured = None;
+            return None;
+        }
+        widget
+            .touch(event)
+            .map(|widget_event| (WidgetId(index), widget_event))
+    }
+
+    /// Redraws `rect` on the next render
+    pub fn invalidate(&mut self, rect: Rect) {
+        self.dirty.add(rect);
+    }
+
+    /// Redraws the changed areas in the framebuffer and returns them
+    pub fn render(&mut self) -> Result<Vec<Rect>> {
+        Ok(self.render_scrolled(None)?.0)
+    }
+
+    /// Renders and sends the changed areas to the display, after vertical blank if
+    /// the display has a `VSync`
+    ///
+    /// Follows the display's size first, so the layout adapts to `set_rotation`. A
+    /// scrolling widget that covers exactly the display's scroll area is scrolled by
+    /// the controller, only the rows moved in are sent.
+    pub fn flush(&mut self, display: &mut TftDisplay) -> Result<()> {
+        if (display.width(), display.height()) != (self.frame.width(), self.frame.height()) {
+            self.resize(display.width(), display.height());
+        }
+
+        let (rects, scrolled) = self.render_scrolled(display.scroll_area())?;
+        if !rects.is_empty() || scrolled != 0 {
+            display.wait_for_vblank()?;
+        }
+        if scrolled != 0 {
+            display.scroll_by(scrolled)?;
+        }
+        for rect in rects {
+            display.flush_rect(&self.frame, rect)?;
+        }
+        Ok(())
+    }
+
+    pub fn framebuffer(&self) -> &Framebuffer {
+        &self.frame
+    }
+
+    /// Renders like `render`, scrolling the framebuffer for the widget that covers
+    /// `scroll_area` and shifting it for charts instead of redrawing them
+    ///
+    /// Returns the changed areas and how many rows the scroll area moved up, the
+    /// display has to scroll by as much before they are sent.
+    pub fn render_scrolled(&mut self, scroll_area: Option<Rect>) -> Result<(Vec<Rect>, i32)> {
+        let now = Instant::now();
+        let widgets = &mut self.widgets;
+        self.animations
+            .retain_mut(|animation| (animation.step)(widgets[animation.index].as_mut(), now));
+        let mut moved = self.close_overlays(now);
+        let mut scrolled = 0;
+        let mut fixed = None;
+        // Overlays would move along with scrolled rows and shifted columns
+        let covered: Vec<Rect> = self.overlays.iter().map(|overlay| overlay.area).collect();
+        for (index, (widget, drawn)) in self.widgets.iter_mut().zip(&mut self.drawn).enumerate() {
+            widget.animate(now);
+
+            // Overlays are drawn over whatever is under them, see `compose`
+            if self.overlays.iter().any(|overlay| overlay.index == index) {
+                if widget.is_dirty() {
+                    self.dirty.add(widget.rect());
+                    widget.state_mut().dirty = false;
+                }
+                continue;
+            }
+
+            let dy = widget.take_scroll();
+            if dy != 0 {
+                let rect = widget.rect();
+                let overlaps = |other: &Rect| other.intersect(rect).is_some();
+                let in_place = *drawn == Some(rect)
+                    && scroll_area == Some(rect)
+                    && !covered.iter().any(overlaps)
+                    && !self.dirty.rects().iter().any(overlaps);
+                if widget.is_dirty() || !in_place || scrolled != 0 {
+                    widget.mark_dirty();
+                } else {
+                    // The framebuffer follows the panel, then the rows moved in and
+                    // anything drawn in place are drawn again
+                    self.frame.scroll(rect, dy);
+                    let (moved, exposed) = split_scrolled(rect, dy);
+                    self.dirty.add(exposed);
+                    // Kept apart so it doesn't merge with the strip into the whole area
+                    fixed = widget
+                        .fixed_rect()
+                        .zip(moved)
+                        .and_then(|(a, b)| a.intersect(b));
+                    scrolled = dy;
+                }
+            }
+
+            if let Some((area, dx)) = widget.take_shift().filter(|&(_, dx)| dx != 0) {
+                let overlaps = |other: &Rect| other.intersect(area).is_some();
+                let in_place = *drawn == Some(widget.rect())
+                    && !covered.iter().any(overlaps)
+                    && !self.dirty.rects().iter().any(overlaps);
+                if widget.is_dirty() || !in_place || dx.unsigned_abs() >= area.w as u32 {
+                    widget.mark_dirty();
+                } else {
+                    // The moved pixels only have to be sent, nothing is drawn there
+                    self.frame.shift(area, dx);
+                    let (kept, exposed) = split_shifted(area, dx);
+                    self.dirty.add(exposed);
+                    if let Some(fixed) = widget.fixed_rect() {
+                        self.dirty.add(fixed);
+                    }
+                    moved.extend(kept.intersect(self.frame.bounds()));
+                }
+            }
+
+            if !widget.is_dirty() {
+                continue;
+            }
+            if let Some(old) = drawn.take() {
+                self.dirty.add(old);
+            }
+            if widget.is_visible() {
+                self.dirty.add(widget.rect());
+                *drawn = Some(widget.rect());
+            }
+            widget.state_mut().dirty = false;
+        }
+
+        let mut rects = self.dirty.take(self.frame.bounds());
+        rects.extend(fixed);
+        for &rect in &rects {
+            self.frame.push_clip(rect);
+            let result = self.compose(rect);
+            self.frame.pop_clip();
+            result?;
+        }
+        rects.extend(moved);
+        Ok((rects, scrolled))
+    }
+
+    fn compose(&mut self, rect: Rect) -> Result<()> {
+        self.frame.fill_rect(rect, self.style.background)?;
+
+        let overlays = &self.overlays;
+        let visible = self.widgets.iter().enumerate().filter(|(index, widget)| {
+            widget.is_visible() && !overlays.iter().any(|overlay| overlay.index == *index)
+        });
+        for (_, widget) in visible {
+            draw_clipped(&mut self.frame, widget.as_ref(), &self.style, rect)?;
+        }
+
+        // What is under each overlay is saved before drawing it
+        for overlay in &mut self.overlays {
+            if let Some(part) = overlay.area.intersect(rect) {
+                let (x, y) = (part.x - overlay.area.x, part.y - overlay.area.y);
+                overlay.under.copy_from(&self.frame, part, x, y);
+            }
+            let widget = self.widgets[overlay.index].as_ref();
+            draw_clipped(&mut self.frame, widget, &self.style, rect)?;
+        }
+        Ok(())
+    }
+
+    fn schedule(
+        &mut self,
+        id: WidgetId,
+        step: impl FnMut(&mut dyn Widget, Instant) -> bool + 'static,
+    ) {
+        if id.0 < self.widgets.len() {
+            let step = Box::new(step);
+            self.animations.push(Animation { index: id.0, step });
+        }
+    }
+
+    fn open(&mut self, id: WidgetId, modal: bool, until: Option<Instant>) {
+        let Some(widget) = self.widgets.get(id.0) else {
+            return;
+        };
+        let area = self.place(widget.rect(), modal);
+        self.overlays.retain(|overlay| overlay.index != id.0);
+
+        let mut under = Framebuffer::new(area.w, area.h, self.style.background);
+        under.copy_from(&self.frame, area, 0, 0);
+        self.overlays.push(Overlay {
+            index: id.0,
+            modal,
+            until,
+            area,
+            under,
+        });
+
+        // Shown as an ordinary widget until now
+        if let Some(drawn) = self.drawn[id.0].take() {
+            self.dirty.add(drawn);
+        }
+        let widget = &mut self.widgets[id.0];
+        widget.set_rect(area);
+        widget.set_visible(true);
+        self.dirty.add(area);
+    }
+
+    /// Where an overlay of `rect`'s size is shown
+    fn place(&self, rect: Rect, modal: bool) -> Rect {
+        let (w, h) = (
+            rect.w.min(self.frame.width()),
+            rect.h.min(self.frame.height()),
+        );
+        let x = (self.frame.width() - w) as i32 / 2;
+        let y = if modal {
+            (self.frame.height() - h) as i32 / 2
+        } else {
+            (self.frame.height().saturating_sub(h + TOAST_MARGIN)) as i32
+        };
+        Rect::new(x, y, w, h)
+    }
+
+    /// Closes overlays that hid themselves or timed out, returns the areas put back
+    ///
+    /// An overlay under another one that stays open is drawn again instead, the pixels
+    /// saved for that one include it.
+    fn close_overlays(&mut self, now: Instant) -> Vec<Rect> {
+        let mut restored = Vec::new();
+        let mut index = self.overlays.len();
+        while index > 0 {
+            index -= 1;
+            let overlay = &self.overlays[index];
+            let widget = &mut self.widgets[overlay.index];
+            if overlay.until.is_some_and(|until| now >= until) {
+                widget.set_visible(false);
+            }
+            if widget.is_visible() {
+                continue;
+            }
+
+            widget.state_mut().dirty = false;
+            let overlay = self.overlays.remove(index);
+            if self.captured == Some(overlay.index) {
+                self.captured = None;
+            }
+            let covered = self.overlays[index..]
+                .iter()
+                .any(|other| other.area.intersect(overlay.area).is_some());
+            if covered {
+                self.dirty.add(overlay.area);
+            } else {
+                self.frame.copy_from(
+                    &overlay.under,
+                    overlay.under.bounds(),
+                    overlay.area.x,
+                    overlay.area.y,
+                );
+                restored.push(overlay.area);
+            }
+        }
+        restored
+    }
+}
+
+/// Draws `widget` where it overlaps `rect`, clipped to both
+fn draw_clipped(
+    canvas: &mut dyn Canvas,
+    widget: &dyn Widget,
+    style: &Style,
+    rect: Rect,
+) -> Result<()> {
+    let Some(area) = widget.rect().intersect(rect) else {
+        return Ok(());
+    };
+    canvas.push_clip(area);
+    let result = widget.draw(canvas, style);
+    canvas.pop_clip();
+    result
+}
+
+/// Rows of `rect` still showing moved content after scrolling by `dy`, and the rows
+/// moved in
+fn split_scrolled(rect: Rect, dy: i32) -> (Option<Rect>, Rect) {
+    let rows = dy.unsigned_abs().min(rect.h as u32) as u16;
+    let kept = rect.h - rows;
+    let (moved, exposed) = if dy > 0 {
+        (rect.y, rect.bottom() - rows as i32)
+    } else {
+        (rect.y + rows as i32, rect.y)
+    };
+    let moved = (kept > 0).then(|| Rect::new(rect.x, moved, rect.w, kept));
+    (moved, Rect::new(rect.x, exposed, rect.w, rows))
+}
+
+/// Columns of `rect` still showing moved content after shifting left by `dx`, which
+/// has to be less than the width, and the columns moved in
+fn split_shifted(rect: Rect, dx: i32) -> (Rect, Rect) {
+    let columns = dx.unsigned_abs() as u16;
+    let kept = rect.w - columns;
+    if dx > 0 {
+        (
+            Rect::new(rect.x, rect.y, kept, rect.h),
+            Rect::new(rect.x + kept as i32, rect.y, columns, rect.h),
+        )
+    } else {
+        (
+            Rect::new(rect.x + columns as i32, rect.y, kept, rect.h),
+            Rect::new(rect.x, rect.y, columns, rect.h),
+        )
+    }
+}
@@ -0,0 +1,182 @@
+use std::{any::Any, time::Instant};
+
+use crate::tft_display::{
+    canvas::Canvas,
+    color::Color,
+    enums::Align,
+    font::text,
+    geometry::{Point, Rect},
+    tft_display::Result,
+};
+use crate::tft_touch::event::TouchEvent;
+use crate::tft_ui::{keyboard::KeyInput, style::Style};
+
+/// What a widget reports after handling touch input
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WidgetEvent {
+    /// Released inside after being pressed
+    Clicked,
+    /// Switched on or off
+    Toggled(bool),
+    /// Value set by dragging
+    Changed(f32),
+    /// One of several items picked, by index
+    Selected(usize),
+    /// Key released on an on-screen keyboard
+    Key(KeyInput),
+}
+
+/// State shared by all widgets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WidgetState {
+    pub rect: Rect,
+    pub enabled: bool,
+    pub visible: bool,
+    pub pressed: bool,
+    /// Changed since it was last drawn
+    pub dirty: bool,
+}
+
+impl WidgetState {
+    pub fn new(rect: Rect) -> Self {
+        Self {
+            rect,
+            enabled: true,
+            visible: true,
+            pressed: false,
+            dirty: true,
+        }
+    }
+
+    /// Sets `pressed` from a touch routed to the widget, `true` for a click
+    ///
+    /// A widget stays pressed only while the touch is inside it, so sliding off
+    /// cancels the click.
+    pub fn track_press(&mut self, event: TouchEvent) -> bool {
+        let inside = self.rect.contains_point(event.point());
+        let pressed = !matches!(event, TouchEvent::Up(_)) && inside;
+        if pressed != self.pressed {
+            self.pressed = pressed;
+            self.dirty = true;
+        }
+        matches!(event, TouchEvent::Up(_)) && inside
+    }
+}
+
+/// Element of the retained UI, drawn by `Ui` whenever it changed
+///
+/// Widgets draw within their rectangle in canvas coordinates, `Ui` clips them to it.
+pub trait Widget: Any {
+    fn state(&self) -> &WidgetState;
+
+    fn state_mut(&mut self) -> &mut WidgetState;
+
+    fn draw(&self, canvas: &mut dyn Canvas, style: &Style) -> Result<()>;
+
+    /// Whether the widget takes touch input
+    fn is_interactive(&self) -> bool {
+        false
+    }
+
+    /// Handles a touch routed to the widget, from its Down up to the matching Up
+    fn touch(&mut self, _event: TouchEvent) -> Option<WidgetEvent> {
+        None
+    }
+
+    fn rect(&self) -> Rect {
+        self.state().rect
+    }
+
+    fn set_rect(&mut self, rect: Rect) {
+        let state = self.state_mut();
+        if state.rect != rect {
+            state.rect = rect;
+            state.dirty = true;
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.state().enabled
+    }
+
+    /// Disabled widgets are grayed out and ignore touch
+    fn set_enabled(&mut self, enabled: bool) {
+        let state = self.state_mut();
+        if state.enabled != enabled {
+            state.enabled = enabled;
+            state.pressed = false;
+            state.dirty = true;
+        }
+    }
+
+    fn is_visible(&self) -> bool {
+        self.state().visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        let state = self.state_mut();
+        if state.visible != visible {
+            state.visible = visible;
+            state.dirty = true;
+        }
+    }
+
+    fn is_pressed(&self) -> bool {
+        self.state().pressed
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.state().dirty
+    }
+
+    /// Draws the widget again on the next render
+    fn mark_dirty(&mut self) {
+        self.state_mut().dirty = true;
+    }
+
+    /// Advances time based state such as momentum, called before every render
+    fn animate(&mut self, _now: Instant) {}
+
+    /// Rows the content moved up since the last call, for scrolling widgets
+    ///
+    /// `Ui` lets the display scroll a widget that covers its scroll area, then draws
+    /// only the rows moved in and `fixed_rect`. Otherwise the widget is redrawn.
+    fn take_scroll(&mut self) -> i32 {
+        0
+    }
+
+    /// Area whose content moved left and by how many columns since the last call
+    ///
+    /// For charts that append data, `Ui` moves the pixels and draws only the columns
+    /// moved in at the right and `fixed_rect`. Otherwise the widget is redrawn.
+    fn take_shift(&mut self) -> Option<(Rect, i32)> {
+        None
+    }
+
+    /// Part drawn in place over scrolling content, e.g. a scroll indicator
+    fn fixed_rect(&self) -> Option<Rect> {
+        None
+    }
+
+    fn hit_test(&self, point: Point) -> bool {
+        self.is_visible() && self.rect().contains_point(point)
+    }
+}
+
+/// Draws `text` wrapped to `rect` and centered vertically
+pub fn draw_text_block(
+    canvas: &mut dyn Canvas,
+    style: &Style,
+    text: &str,
+    rect: Rect,
+    align: Align,
+    color: Color,
+) -> Result<()> {
+    let font = style.font.as_ref();
+    let lines = text::wrap(font, text, rect.w).len() as i32;
+    let height = lines * font.line_metrics().line_height;
+    let top = rect.y + (rect.h as i32 - height).max(0) / 2;
+
+    let bounds = Rect::new(rect.x, top, rect.w, (rect.bottom() - top) as u16);
+    canvas.draw_text_box(font, text, bounds, align, color, None)
+}